@@ -1,4 +1,4 @@
-use core::cmp::Ord;
+use core::{alloc::Layout, cmp::Ord, fmt};
 
 /// A trait for memory address types.
 ///
@@ -20,11 +20,40 @@ pub trait MemoryAddr:
 {
     // No required methods for now. Following are some utility methods.
 
+    //
+    // This section contains sentinel values of the address type.
+    //
+
+    /// Returns the maximum address, i.e., `Self::from(usize::MAX)`.
+    #[inline]
+    #[must_use]
+    fn max_addr() -> Self {
+        Self::from(usize::MAX)
+    }
+
+    /// Returns the minimum address, i.e., `Self::from(0)`.
+    #[inline]
+    #[must_use]
+    fn min_addr() -> Self {
+        Self::from(0)
+    }
+
+    /// Checks whether the address is zero, which is usually treated as the
+    /// null pointer.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn is_null(self) -> bool {
+        self.into() == 0
+    }
+
     //
     // This section contains utility methods for address alignment.
     //
 
     /// Aligns the address downwards to the given alignment.
+    ///
+    /// The alignment can be of any type convertible to `usize`, including
+    /// address types, e.g., a superpage size stored as a [`PhysAddr`].
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
     fn align_down<U>(self, align: U) -> Self
@@ -54,6 +83,47 @@ pub trait MemoryAddr:
         crate::align_offset(self.into(), align.into())
     }
 
+    /// Returns the distance from the address to the next alignment boundary,
+    /// i.e., the number of bytes to add to align the address upwards.
+    ///
+    /// Returns `0` if the address is already aligned.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn align_up_offset<U>(self, align: U) -> usize
+    where
+        U: Into<usize>,
+    {
+        crate::align_offset(self.into().wrapping_neg(), align.into())
+    }
+
+    /// Returns the distance from the address to the next address having the
+    /// same offset within the given alignment as `other`, i.e., the number of
+    /// bytes to add to co-align the address with `other`.
+    ///
+    /// It's `(other - self) mod align`, which is `0` if both addresses already
+    /// have the same offset. With an aligned `other`, it's the same as
+    /// `align_up_offset`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{pa, va, MemoryAddr};
+    ///
+    /// // Co-align a virtual address with a physical address to use 2M pages.
+    /// let (vaddr, paddr) = (va!(0x1234_5000), pa!(0x4567_8000));
+    /// let distance = vaddr.distance_to_alignment_of(va!(paddr.as_usize()), 0x20_0000usize);
+    /// assert_eq!(distance, 0x13_3000);
+    /// assert_eq!((vaddr + distance).align_offset(0x20_0000usize), paddr.align_offset(0x20_0000usize));
+    /// ```
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn distance_to_alignment_of<U>(self, other: Self, align: U) -> usize
+    where
+        U: Into<usize>,
+    {
+        crate::align_offset(other.wrapping_sub_addr(self), align.into())
+    }
+
     /// Checks whether the address has the demanded alignment.
     #[inline]
     #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
@@ -64,6 +134,67 @@ pub trait MemoryAddr:
         crate::is_aligned(self.into(), align.into())
     }
 
+    /// Aligns the address downwards to the alignment given as a `const`
+    /// generic parameter.
+    ///
+    /// With `ALIGN` known at compile time, the mask folds to a constant. It's
+    /// checked at compile time that `ALIGN` is a power of two.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{va, MemoryAddr};
+    ///
+    /// assert_eq!(va!(0x1234).align_down_const::<0x1000>(), va!(0x1000));
+    /// ```
+    ///
+    /// And this won't compile, since the alignment is not a power of two:
+    ///
+    /// ```compile_fail
+    /// use memory_addr::{va, MemoryAddr};
+    ///
+    /// let _ = va!(0x1234).align_down_const::<0x1001>();
+    /// ```
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_down_const<const ALIGN: usize>(self) -> Self {
+        const { assert!(ALIGN.is_power_of_two(), "alignment must be a power of two") };
+        Self::from(crate::align_down(self.into(), ALIGN))
+    }
+
+    /// Aligns the address upwards to the alignment given as a `const` generic
+    /// parameter.
+    ///
+    /// With `ALIGN` known at compile time, the mask folds to a constant. It's
+    /// checked at compile time that `ALIGN` is a power of two. It behaves the
+    /// same as `align_up` on overflow.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_up_const<const ALIGN: usize>(self) -> Self {
+        const { assert!(ALIGN.is_power_of_two(), "alignment must be a power of two") };
+        Self::from(crate::align_up(self.into(), ALIGN))
+    }
+
+    /// Wraps the address in [`Aligned`](crate::Aligned) as a proof of its
+    /// alignment, returning `None` if it's not aligned to `ALIGN`.
+    ///
+    /// It's the same as [`Aligned::new`](crate::Aligned::new).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{pa, MemoryAddr};
+    ///
+    /// let paddr = pa!(0x20_0000).try_aligned::<0x20_0000>().unwrap();
+    /// assert_eq!(paddr.get(), pa!(0x20_0000));
+    /// assert!(pa!(0x1000).try_aligned::<0x20_0000>().is_none());
+    /// ```
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn try_aligned<const ALIGN: usize>(self) -> Option<crate::Aligned<Self, ALIGN>> {
+        crate::Aligned::new(self)
+    }
+
     /// Aligns the address downwards to 4096 (bytes).
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
@@ -92,626 +223,4410 @@ pub trait MemoryAddr:
         crate::is_aligned(self.into(), crate::PAGE_SIZE_4K)
     }
 
-    //
-    // This section contains utility methods for address arithmetic.
-    //
-
-    /// Adds a given offset to the address to get a new address.
-    /// 
+    /// Returns the next alignment boundary strictly above the address.
+    ///
+    /// Unlike `align_up`, an already aligned address is advanced by `align`
+    /// instead of being returned unchanged.
+    ///
     /// # Panics
-    /// 
+    ///
     /// Panics if the result overflows.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn offset(self, offset: isize) -> Self {
-        // todo: use `strict_add_signed` when it's stable.
-        Self::from(usize::checked_add_signed(self.into(), offset).expect("overflow in `MemoryAddr::offset`"))
+    fn next_aligned<U>(self, align: U) -> Self
+    where
+        U: Into<usize>,
+    {
+        let (addr, align) = (self.into(), align.into());
+        match usize::checked_add(crate::align_down(addr, align), align) {
+            Some(addr) => Self::from(addr),
+            None => panic!(
+                "overflow in `MemoryAddr::next_aligned`: {:#x} aligned to {:#x}",
+                addr, align
+            ),
+        }
     }
 
-    /// Adds a given offset to the address to get a new address.
-    /// 
-    /// Unlike `offset`, this method always wraps around on overflow.
+    /// Returns the previous alignment boundary strictly below the address.
+    ///
+    /// Unlike `align_down`, an already aligned address is moved back by
+    /// `align` instead of being returned unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result underflows.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn wrapping_offset(self, offset: isize) -> Self {
-        Self::from(usize::wrapping_add_signed(self.into(), offset))
+    fn prev_aligned<U>(self, align: U) -> Self
+    where
+        U: Into<usize>,
+    {
+        let (addr, align) = (self.into(), align.into());
+        match usize::checked_sub(addr, 1) {
+            Some(addr) => Self::from(crate::align_down(addr, align)),
+            None => panic!(
+                "overflow in `MemoryAddr::prev_aligned`: {:#x} aligned to {:#x}",
+                addr, align
+            ),
+        }
     }
 
-    /// Gets the distance between two addresses.
-    /// 
-    /// # Panics
-    /// 
-    /// Panics if the result is not representable by `isize`.
+    /// Aligns the address downwards to the given alignment, and reports
+    /// whether the address has been changed.
+    ///
+    /// The returned flag is `false` iff the address is already aligned.
     #[inline]
-    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
-    fn offset_from(self, base: Self) -> isize {
-        let result = usize::wrapping_sub(self.into(), base.into()) as isize;
-        if (result > 0) ^ (base < self) {
-            // The result has overflowed.
-            panic!("overflow in `MemoryAddr::offset_from`");
-        } else {
-            result
-        }
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_down_changed<U>(self, align: U) -> (Self, bool)
+    where
+        U: Into<usize>,
+    {
+        let addr = self.into();
+        let aligned = crate::align_down(addr, align.into());
+        (Self::from(aligned), aligned != addr)
     }
 
-    /// Adds a given **unsigned** offset to the address to get a new address.
-    /// 
-    /// This method is similar to `offset`, but it takes an unsigned offset.
-    /// 
-    /// # Panics
-    /// 
-    /// Panics if the result overflows.
+    /// Aligns the address upwards to the given alignment, and reports whether
+    /// the address has been changed.
+    ///
+    /// The returned flag is `false` iff the address is already aligned.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn add(self, rhs: usize) -> Self {
-        Self::from(usize::checked_add(self.into(), rhs).expect("overflow in `MemoryAddr::add`"))
+    fn align_up_changed<U>(self, align: U) -> (Self, bool)
+    where
+        U: Into<usize>,
+    {
+        let addr = self.into();
+        let aligned = crate::align_up(addr, align.into());
+        (Self::from(aligned), aligned != addr)
     }
 
-    /// Adds a given **unsigned** offset to the address to get a new address.
-    /// 
-    /// Unlike `add`, this method always wraps around on overflow.
+    /// Aligns the address downwards to the given alignment, returning the
+    /// aligned address and the mask `align - 1` whose bits were cleared.
+    ///
+    /// It's the same as `align_down`, but exposes the mask, e.g., for
+    /// debugging off-by-one alignment issues.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn wrapping_add(self, rhs: usize) -> Self {
-        Self::from(usize::wrapping_add(self.into(), rhs))
+    fn align_down_with_mask<U>(self, align: U) -> (Self, usize)
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        (self.align_down(align), align - 1)
     }
 
-    /// Adds a given **unsigned** offset to the address to get a new address.
-    /// 
-    /// Unlike `add`, this method returns a tuple of the new address and a boolean indicating
-    /// whether the addition has overflowed.
+    /// Splits the address into the aligned base and the offset within the
+    /// given alignment.
+    ///
+    /// It's guaranteed that `base + offset == self`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn split_align<U>(self, align: U) -> (Self, usize)
+    where
+        U: Into<usize>,
+    {
+        let (addr, align) = (self.into(), align.into());
+        (
+            Self::from(crate::align_down(addr, align)),
+            crate::align_offset(addr, align),
+        )
+    }
+
+    /// Splits the address into the 4K-aligned page base and the offset within
+    /// the page.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn split_4k(self) -> (Self, usize) {
+        self.split_align(crate::PAGE_SIZE_4K)
+    }
+
+    /// Replaces the offset of the address within the page of size `page_size` with `offset`,
+    /// i.e., returns `self.align_down(page_size) + offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset >= page_size`.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn overflowing_add(self, rhs: usize) -> (Self, bool) {
-        let (result, overflow) = self.into().overflowing_add(rhs);
-        (Self::from(result), overflow)
+    fn with_page_offset(self, offset: usize, page_size: usize) -> Self {
+        assert!(offset < page_size, "offset out of page in `MemoryAddr::with_page_offset`");
+        Self::from(crate::align_down(self.into(), page_size) | offset)
     }
 
-    /// Adds a given **unsigned** offset to the address to get a new address.
-    /// 
-    /// Unlike `add`, this method returns `None` on overflow.
+    /// Checks whether the region `[self, self + len)` crosses a boundary of `boundary`, i.e.,
+    /// whether its first and last bytes lie in different blocks aligned to `boundary`, e.g.,
+    /// for DMA engines that can't cross a page boundary in a single transfer.
+    ///
+    /// `boundary` must be a power of two, which is checked in debug builds. An empty region
+    /// never crosses a boundary, while a region running past `usize::MAX` always does.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn crosses_boundary<U>(self, len: usize, boundary: U) -> bool
+    where
+        U: Into<usize>,
+    {
+        let boundary = boundary.into();
+        len > boundary - crate::align_offset(self.into(), boundary)
+    }
+
+    /// Checks whether the region `[self, self + len)` crosses a 4K page boundary.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn crosses_page_4k(self, len: usize) -> bool {
+        self.crosses_boundary(len, crate::PAGE_SIZE_4K)
+    }
+
+    /// Checks whether the region `[self, self + size)` can be mapped by a superpage of size
+    /// `page_size` at its start, i.e., the address is aligned to `page_size` and the region is
+    /// not smaller than `page_size`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn can_use_superpage<U>(self, size: usize, page_size: U) -> bool
+    where
+        U: Into<usize>,
+    {
+        let page_size = page_size.into();
+        self.is_aligned(page_size) && size >= page_size
+    }
+
+    /// Checks whether the region `[self, self + size)` can be mapped by a 2M superpage at
+    /// its start.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn can_use_superpage_2m(self, size: usize) -> bool {
+        self.can_use_superpage(size, crate::PAGE_SIZE_2M)
+    }
+
+    /// Checks whether the region `[self, self + size)` can be mapped by a 1G superpage at
+    /// its start.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn can_use_superpage_1g(self, size: usize) -> bool {
+        self.can_use_superpage(size, crate::PAGE_SIZE_1G)
+    }
+
+    /// Returns the greatest alignment among `candidates` that the address satisfies, e.g., the
+    /// largest page size that can be used to map the address.
+    ///
+    /// Returns `None` if the address satisfies none of them. The candidates must be powers of
+    /// two, which is checked in debug builds.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn largest_alignment(self, candidates: &[usize]) -> Option<usize> {
+        candidates.iter().copied().filter(|&align| self.is_aligned(align)).max()
+    }
+
+    /// Aligns the address downwards to the size of a block of `2^order` 4K
+    /// pages (see [`page_size_of_order`](crate::page_size_of_order)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size is not representable by `usize`.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn checked_add(self, rhs: usize) -> Option<Self> {
-        usize::checked_add(self.into(), rhs).map(Self::from)
+    fn align_down_order(self, order: u32) -> Self {
+        self.align_down(crate::page_size_of_order(order))
     }
 
-    /// Subtracts a given **unsigned** offset from the address to get a new address.
-    /// 
-    /// This method is similar to `offset(-rhs)`, but it takes an unsigned offset. 
-    /// 
+    /// Aligns the address upwards to the size of a block of `2^order` 4K
+    /// pages (see [`page_size_of_order`](crate::page_size_of_order)).
+    ///
     /// # Panics
-    /// 
-    /// Panics if the result overflows.
+    ///
+    /// Panics if the block size is not representable by `usize`.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn sub(self, rhs: usize) -> Self {
-        Self::from(usize::checked_sub(self.into(), rhs).expect("overflow in `MemoryAddr::sub`"))
+    fn align_up_order(self, order: u32) -> Self {
+        self.align_up(crate::page_size_of_order(order))
     }
 
-    /// Subtracts a given **unsigned** offset from the address to get a new address.
-    /// 
-    /// Unlike `sub`, this method always wraps around on overflowed.
+    /// Aligns the address upwards to the size of a block of `2^order` 4K
+    /// pages.
+    ///
+    /// Unlike `align_up_order`, this method returns `None` if the block size
+    /// is not representable by `usize`, or if the result overflows.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn wrapping_sub(self, rhs: usize) -> Self {
-        Self::from(usize::wrapping_sub(self.into(), rhs))
+    fn checked_align_up_to_order(self, order: u32) -> Option<Self> {
+        if order >= usize::BITS - crate::PAGE_SHIFT_4K as u32 {
+            return None;
+        }
+        self.checked_align_up(crate::page_size_of_order(order))
     }
 
-    /// Subtracts a given **unsigned** offset from the address to get a new address.
-    /// 
-    /// Unlike `sub`, this method returns a tuple of the new address and a boolean indicating
-    /// whether the subtraction has overflowed.
+    /// Aligns the address downwards to `1 << shift`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shift` is not less than `usize::BITS`.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn overflowing_sub(self, rhs: usize) -> (Self, bool) {
-        let (result, overflow) = self.into().overflowing_sub(rhs);
-        (Self::from(result), overflow)
+    fn align_down_shift(self, shift: u32) -> Self {
+        let mask = shift_mask(shift, "align_down_shift");
+        Self::from(self.into() & !mask)
     }
 
-    /// Subtracts a given **unsigned** offset from the address to get a new address.
-    /// 
-    /// Unlike `sub`, this method returns `None` on overflow.
+    /// Aligns the address upwards to `1 << shift`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shift` is not less than `usize::BITS`, or if the result
+    /// overflows.
     #[inline]
     #[must_use = "this returns a new address, without modifying the original"]
-    fn checked_sub(self, rhs: usize) -> Option<Self> {
-        usize::checked_sub(self.into(), rhs).map(Self::from)
+    fn align_up_shift(self, shift: u32) -> Self {
+        let mask = shift_mask(shift, "align_up_shift");
+        let addr = self.into();
+        match usize::checked_add(addr, mask) {
+            Some(addr) => Self::from(addr & !mask),
+            None => panic!(
+                "overflow in `MemoryAddr::align_up_shift`: {:#x} aligned to {:#x}",
+                addr,
+                mask + 1
+            ),
+        }
     }
 
-    /// Subtracts another address from the address to get the offset between them.
-    /// 
+    /// Checks whether the address is aligned to `1 << shift`.
+    ///
     /// # Panics
-    /// 
-    /// Panics if the result overflows.
+    ///
+    /// Panics if `shift` is not less than `usize::BITS`.
     #[inline]
     #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
-    fn sub_addr(self, rhs: Self) -> usize {
-        usize::checked_sub(self.into(), rhs.into()).expect("overflow in `MemoryAddr::sub_addr`")
+    fn is_aligned_shift(self, shift: u32) -> bool {
+        self.into() & shift_mask(shift, "is_aligned_shift") == 0
     }
 
-    /// Subtracts another address from the address to get the offset between them.
-    /// 
-    /// Unlike `sub_addr`, this method always wraps around on overflow.
+    /// Returns the number of trailing zeros in the binary representation of the address, i.e.,
+    /// `log2` of the largest power-of-two alignment that the address satisfies.
+    ///
+    /// Returns `usize::BITS` for the null address.
     #[inline]
     #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
-    fn wrapping_sub_addr(self, rhs: Self) -> usize {
-        usize::wrapping_sub(self.into(), rhs.into())
+    fn trailing_zeros(self) -> u32 {
+        self.into().trailing_zeros()
     }
 
-    /// Subtracts another address from the address to get the offset between them.
-    /// 
-    /// Unlike `sub_addr`, this method returns a tuple of the offset and a boolean indicating
-    /// whether the subtraction has overflowed.
+    /// Returns the number of leading zeros in the binary representation of the address.
     #[inline]
     #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
-    fn overflowing_sub_addr(self, rhs: Self) -> (usize, bool) {
-        usize::overflowing_sub(self.into(), rhs.into())
+    fn leading_zeros(self) -> u32 {
+        self.into().leading_zeros()
     }
 
-    /// Subtracts another address from the address to get the offset between them.
-    /// 
-    /// Unlike `sub_addr`, this method returns `None` on overflow.
+    /// Reverses the byte order of the address.
     #[inline]
-    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
-    fn checked_sub_addr(self, rhs: Self) -> Option<usize> {
-        usize::checked_sub(self.into(), rhs.into())
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn swap_bytes(self) -> Self {
+        Self::from(self.into().swap_bytes())
     }
-}
 
-/// Implement the `MemoryAddr` trait for any type that is `Copy`, `From<usize>`,
-/// `Into<usize>`, and `Ord`.
-impl<T> MemoryAddr for T where T: Copy + From<usize> + Into<usize> + Ord {}
+    /// Converts the address from the native endianness to little endian, e.g., before writing
+    /// it for a consumer of a different endianness.
+    ///
+    /// On little endian targets this is a no-op, otherwise the bytes are swapped.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn to_le(self) -> Self {
+        Self::from(self.into().to_le())
+    }
 
-/// Creates a new address type by wrapping an `usize`.
-///
-/// For each `$vis type $name;`, this macro generates the following items:
-/// - Definition of the new address type `$name`, which contains a single
-///   private unnamed field of type `usize`.
-/// - Default implementations (i.e. derived implementations) for the following
-///   traits:
-///   - `Copy`, `Clone`,
-///   - `Default`,
-///   - `Ord`, `PartialOrd`, `Eq`, and `PartialEq`.
-/// - Implementations for the following traits:
-///   - `From<usize>`, `Into<usize>` (by implementing `From<$name> for usize`),
-///   - `Add<usize>`, `AddAssign<usize>`, `Sub<usize>`, `SubAssign<usize>`, and
-///   - `Sub<$name>`.
-/// - Two `const` methods to convert between the address type and `usize`:
-///   - `from_usize`, which converts an `usize` to the address type, and
-///   - `as_usize`, which converts the address type to an `usize`.
-///
-/// # Example
-///
-/// ```
-/// use memory_addr::{def_usize_addr, MemoryAddr};
-///
-/// def_usize_addr! {
-///     /// A example address type.
-///     #[derive(Debug)]
-///     pub type ExampleAddr;
-/// }
-///
-/// # fn main() {
-/// const EXAMPLE: ExampleAddr = ExampleAddr::from_usize(0x1234);
-/// const EXAMPLE_USIZE: usize = EXAMPLE.as_usize();
-/// assert_eq!(EXAMPLE_USIZE, 0x1234);
-/// assert_eq!(EXAMPLE.align_down(0x10usize), ExampleAddr::from_usize(0x1230));
-/// assert_eq!(EXAMPLE.align_up_4k(), ExampleAddr::from_usize(0x2000));
-/// # }
-/// ```
-#[macro_export]
-macro_rules! def_usize_addr {
-    (
-        $(#[$meta:meta])*
-        $vis:vis type $name:ident;
+    /// Converts the address from the native endianness to big endian.
+    ///
+    /// On big endian targets this is a no-op, otherwise the bytes are swapped.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn to_be(self) -> Self {
+        Self::from(self.into().to_be())
+    }
 
-        $($tt:tt)*
-    ) => {
-        #[repr(transparent)]
-        #[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq)]
-        $(#[$meta])*
-        pub struct $name(usize);
+    /// Aligns the address downwards to the given 64-bit alignment, e.g., an alignment read
+    /// from a hardware table, which may not fit in `usize` on 32-bit targets.
+    ///
+    /// An alignment larger than `usize::MAX` is not truncated. As no address other than zero is
+    /// aligned to it, the result is always zero in this case.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_down_u64(self, align: u64) -> Self {
+        debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
+        match usize::try_from(align) {
+            Ok(align) => self.align_down(align),
+            Err(_) => Self::from(0),
+        }
+    }
 
-        impl $name {
-            #[doc = concat!("Converts an `usize` to an [`", stringify!($name), "`].")]
-            #[inline]
-            pub const fn from_usize(addr: usize) -> Self {
-                Self(addr)
-            }
-
-            #[doc = concat!("Converts an [`", stringify!($name), "`] to an `usize`.")]
-            #[inline]
-            pub const fn as_usize(self) -> usize {
-                self.0
-            }
+    /// Aligns the address upwards to the given 64-bit alignment, e.g., an alignment read
+    /// from a hardware table, which may not fit in `usize` on 32-bit targets.
+    ///
+    /// An alignment larger than `usize::MAX` is not truncated. As no address other than zero is
+    /// aligned to it, the address is returned unchanged if it's zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alignment is larger than `usize::MAX` and the address is not zero, as the
+    /// result overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_up_u64(self, align: u64) -> Self {
+        debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
+        match usize::try_from(align) {
+            Ok(align) => self.align_up(align),
+            Err(_) if self.into() == 0 => self,
+            Err(_) => panic!(
+                "overflow in `MemoryAddr::align_up_u64`: {:#x} aligned to {:#x}",
+                self.into(),
+                align
+            ),
         }
+    }
 
-        impl From<usize> for $name {
-            #[inline]
-            fn from(addr: usize) -> Self {
-                Self(addr)
-            }
-        }
+    /// Returns the number of the 4K page containing the address, i.e., the
+    /// page frame number (PFN) if the address is physical.
+    ///
+    /// The offset within the page is discarded.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn page_number_4k(self) -> usize {
+        self.into() >> crate::PAGE_SHIFT_4K
+    }
 
-        impl From<$name> for usize {
-            #[inline]
-            fn from(addr: $name) -> usize {
-                addr.0
-            }
+    /// Returns the start address of the 4K page with the given page number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows.
+    #[inline]
+    #[must_use]
+    fn from_page_number_4k(page_number: usize) -> Self {
+        match usize::checked_mul(page_number, crate::PAGE_SIZE_4K) {
+            Some(addr) => Self::from(addr),
+            None => panic!(
+                "overflow in `MemoryAddr::from_page_number_4k`: {:#x} * {:#x}",
+                page_number,
+                crate::PAGE_SIZE_4K
+            ),
         }
+    }
 
-        impl core::ops::Add<usize> for $name {
-            type Output = Self;
-            #[inline]
-            fn add(self, rhs: usize) -> Self {
-                Self(self.0 + rhs)
-            }
+    /// Aligns the address downwards to the given alignment.
+    ///
+    /// Unlike `align_down`, this method returns `None` if `align` is not a
+    /// power of two (including zero), so it's safe for untrusted alignments.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_align_down<U>(self, align: U) -> Option<Self>
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        if align.is_power_of_two() {
+            Some(Self::from(crate::align_down(self.into(), align)))
+        } else {
+            None
         }
+    }
 
-        impl core::ops::AddAssign<usize> for $name {
-            #[inline]
-            fn add_assign(&mut self, rhs: usize) {
-                self.0 += rhs;
-            }
+    /// Aligns the address upwards to the given alignment.
+    ///
+    /// Unlike `align_up`, this method returns `None` if `align` is not a
+    /// power of two (including zero), or if the result overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_align_up<U>(self, align: U) -> Option<Self>
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        if align.is_power_of_two() {
+            let mask = align - 1;
+            usize::checked_add(self.into(), mask).map(|addr| Self::from(addr & !mask))
+        } else {
+            None
         }
+    }
 
-        impl core::ops::Sub<usize> for $name {
-            type Output = Self;
-            #[inline]
-            fn sub(self, rhs: usize) -> Self {
-                Self(self.0 - rhs)
-            }
+    /// Aligns the address upwards to the given alignment.
+    ///
+    /// Unlike `checked_align_up`, this method tells why the alignment failed:
+    /// [`AlignError::NotPowerOfTwo`] if `align` is not a power of two
+    /// (including zero), or [`AlignError::Overflow`] if the result overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn try_align_up<U>(self, align: U) -> Result<Self, AlignError>
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        if !align.is_power_of_two() {
+            return Err(AlignError::NotPowerOfTwo);
         }
+        self.checked_align_up(align).ok_or(AlignError::Overflow)
+    }
 
-        impl core::ops::SubAssign<usize> for $name {
-            #[inline]
-            fn sub_assign(&mut self, rhs: usize) {
-                self.0 -= rhs;
-            }
+    /// Aligns the address upwards to 4096 (bytes).
+    ///
+    /// Unlike `align_up_4k`, this method returns `None` if the result
+    /// overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_align_up_4k(self) -> Option<Self> {
+        self.checked_align_up(crate::PAGE_SIZE_4K)
+    }
+
+    /// Returns the next 4K page boundary strictly above the address, i.e., the
+    /// address aligned upwards if it's not aligned, or advanced by 4K
+    /// otherwise.
+    ///
+    /// It's the same as `next_aligned(PAGE_SIZE_4K)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn next_page_4k(self) -> Self {
+        match self.checked_next_page_4k() {
+            Some(addr) => addr,
+            None => panic!(
+                "overflow in `MemoryAddr::next_page_4k`: {:#x} aligned to {:#x}",
+                self.into(),
+                crate::PAGE_SIZE_4K
+            ),
         }
+    }
 
-        impl core::ops::Sub<$name> for $name {
-            type Output = usize;
-            #[inline]
-            fn sub(self, rhs: $name) -> usize {
-                self.0 - rhs.0
-            }
+    /// Returns the previous 4K page boundary strictly below the address, i.e.,
+    /// the address aligned downwards if it's not aligned, or moved back by 4K
+    /// otherwise.
+    ///
+    /// It's the same as `prev_aligned(PAGE_SIZE_4K)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result underflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn prev_page_4k(self) -> Self {
+        match self.checked_prev_page_4k() {
+            Some(addr) => addr,
+            None => panic!(
+                "overflow in `MemoryAddr::prev_page_4k`: {:#x} aligned to {:#x}",
+                self.into(),
+                crate::PAGE_SIZE_4K
+            ),
         }
+    }
 
-        $crate::def_usize_addr!($($tt)*);
-    };
-    () => {};
-}
+    /// Returns the next 4K page boundary strictly above the address.
+    ///
+    /// Unlike `next_page_4k`, this method returns `None` if the result
+    /// overflows, so that page-walking loops can terminate cleanly at the top
+    /// of the address space.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_next_page_4k(self) -> Option<Self> {
+        self.align_down_4k().checked_add(crate::PAGE_SIZE_4K)
+    }
 
-/// Creates implementations for the [`Debug`](core::fmt::Debug),
-/// [`LowerHex`](core::fmt::LowerHex), and [`UpperHex`](core::fmt::UpperHex)
-/// traits for the given address types defined by the [`def_usize_addr`].
-///
-/// For each `$name = $format;`, this macro generates the following items:
-/// - An implementation of [`core::fmt::Debug`] for the address type `$name`,
-///   which formats the address with `format_args!($format,
-///   format_args!("{:#x}", self.0))`,
-/// - An implementation of [`core::fmt::LowerHex`] for the address type `$name`,
-///   which formats the address in the same way as [`core::fmt::Debug`],
-/// - An implementation of [`core::fmt::UpperHex`] for the address type `$name`,
-///   which formats the address with `format_args!($format,
-///   format_args!("{:#X}", self.0))`.
-///
-/// # Example
-///
-/// ```
-/// use memory_addr::{PhysAddr, VirtAddr, def_usize_addr, def_usize_addr_formatter};
-///
-/// def_usize_addr! {
-///     /// An example address type.
-///     pub type ExampleAddr;
-/// }
-///
-/// def_usize_addr_formatter! {
-///     ExampleAddr = "EA:{}";
-/// }
-///
-/// # fn main() {
-/// assert_eq!(format!("{:?}", PhysAddr::from(0x1abc)), "PA:0x1abc");
-/// assert_eq!(format!("{:x}", VirtAddr::from(0x1abc)), "VA:0x1abc");
-/// assert_eq!(format!("{:X}", ExampleAddr::from(0x1abc)), "EA:0x1ABC");
-/// # }
-/// ```
-#[macro_export]
-macro_rules! def_usize_addr_formatter {
-    (
-        $name:ident = $format:literal;
+    /// Returns the previous 4K page boundary strictly below the address.
+    ///
+    /// Unlike `prev_page_4k`, this method returns `None` if the result
+    /// underflows, i.e., if the address is zero.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_prev_page_4k(self) -> Option<Self> {
+        usize::checked_sub(self.into(), 1).map(|addr| Self::from(crate::align_down_4k(addr)))
+    }
 
-        $($tt:tt)*
-    ) => {
-        impl core::fmt::Debug for $name {
-            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                f.write_fmt(format_args!($format, format_args!("{:#x}", self.0)))
-            }
-        }
+    /// Aligns the address downwards to the given alignment.
+    ///
+    /// It's the same as `align_down`, which never overflows, and is provided
+    /// for symmetry with `wrapping_align_up`.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn wrapping_align_down<U>(self, align: U) -> Self
+    where
+        U: Into<usize>,
+    {
+        self.align_down(align)
+    }
 
-        impl core::fmt::LowerHex for $name {
-            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                f.write_fmt(format_args!($format, format_args!("{:#x}", self.0)))
-            }
-        }
+    /// Aligns the address upwards to the given alignment.
+    ///
+    /// Unlike `align_up`, this method wraps around to zero instead of
+    /// overflowing when rounding up past `usize::MAX`.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn wrapping_align_up<U>(self, align: U) -> Self
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
+        Self::from(usize::wrapping_add(self.into(), align - 1) & !(align - 1))
+    }
 
-        impl core::fmt::UpperHex for $name {
-            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                f.write_fmt(format_args!($format, format_args!("{:#X}", self.0)))
-            }
+    /// Returns the offset of the address within the given alignment.
+    ///
+    /// Unlike `align_offset`, this method returns `None` if `align` is not a
+    /// power of two (including zero), so it's safe for untrusted alignments.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_align_offset<U>(self, align: U) -> Option<usize>
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        if align.is_power_of_two() {
+            Some(crate::align_offset(self.into(), align))
+        } else {
+            None
         }
+    }
 
-        $crate::def_usize_addr_formatter!($($tt)*);
-    };
-    () => {};
-}
-
-def_usize_addr! {
-    /// A physical memory address.
-    pub type PhysAddr;
+    /// Aligns the address downwards to the given alignment, without checking
+    /// the validity of the alignment.
+    ///
+    /// # Safety
+    ///
+    /// `align` must be a power of two. Otherwise the behavior is undefined.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    unsafe fn align_down_unchecked(self, align: usize) -> Self {
+        // SAFETY: guaranteed by the caller.
+        unsafe { core::hint::assert_unchecked(align.is_power_of_two()) };
+        Self::from(self.into() & !(align - 1))
+    }
 
-    /// A virtual memory address.
-    pub type VirtAddr;
-}
+    /// Aligns the address upwards to the given alignment, without checking
+    /// the validity of the alignment or overflow.
+    ///
+    /// # Safety
+    ///
+    /// `align` must be a power of two, and `self + (align - 1)` must not
+    /// overflow `usize`. Otherwise the behavior is undefined.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    unsafe fn align_up_unchecked(self, align: usize) -> Self {
+        // SAFETY: guaranteed by the caller.
+        unsafe {
+            core::hint::assert_unchecked(align.is_power_of_two());
+            Self::from(self.into().unchecked_add(align - 1) & !(align - 1))
+        }
+    }
 
-def_usize_addr_formatter! {
-    PhysAddr = "PA:{}";
-    VirtAddr = "VA:{}";
-}
+    /// Aligns the address downwards to the alignment of the given layout.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_down_for_layout(self, layout: Layout) -> Self {
+        self.align_down(layout.align())
+    }
 
-impl VirtAddr {
-    /// Creates a new virtual address from a raw pointer.
+    /// Aligns the address upwards to the alignment of the given layout.
     #[inline]
-    pub fn from_ptr_of<T>(ptr: *const T) -> Self {
-        Self(ptr as usize)
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn align_up_for_layout(self, layout: Layout) -> Self {
+        self.align_up(layout.align())
     }
 
-    /// Creates a new virtual address from a mutable raw pointer.
+    /// Checks whether an allocation of the given layout fits in the region
+    /// `[self, region_end)`, after aligning the address upwards to the
+    /// alignment of the layout.
     #[inline]
-    pub fn from_mut_ptr_of<T>(ptr: *mut T) -> Self {
-        Self(ptr as usize)
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn fits_layout(self, layout: Layout, region_end: Self) -> bool {
+        let mask = layout.align() - 1;
+        usize::checked_add(self.into(), mask)
+            .and_then(|addr| usize::checked_add(addr & !mask, layout.size()))
+            .is_some_and(|end| end <= region_end.into())
     }
 
-    /// Converts the virtual address to a raw pointer.
+    //
+    // This section contains utility methods for address arithmetic.
+    //
+
+    /// Adds a given offset to the address to get a new address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the result overflows. The panic message includes both operands.
     #[inline]
-    pub const fn as_ptr(self) -> *const u8 {
-        self.0 as *const u8
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn offset(self, offset: isize) -> Self {
+        // todo: use `strict_add_signed` when it's stable.
+        let addr = self.into();
+        match usize::checked_add_signed(addr, offset) {
+            Some(addr) => Self::from(addr),
+            None if offset < 0 => {
+                panic!("overflow in `MemoryAddr::offset`: {:#x} - {:#x}", addr, offset.unsigned_abs())
+            }
+            None => panic!("overflow in `MemoryAddr::offset`: {:#x} + {:#x}", addr, offset),
+        }
     }
 
-    /// Converts the virtual address to a raw pointer of a specific type.
+    /// Adds a given offset to the address to get a new address.
+    /// 
+    /// Unlike `offset`, this method always wraps around on overflow.
     #[inline]
-    pub const fn as_ptr_of<T>(self) -> *const T {
-        self.0 as *const T
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn wrapping_offset(self, offset: isize) -> Self {
+        Self::from(usize::wrapping_add_signed(self.into(), offset))
     }
 
-    /// Converts the virtual address to a mutable raw pointer.
+    /// Adds a given **signed** offset to the address to get a new address.
+    /// 
+    /// Unlike `offset`, this method returns `None` on overflow. It's named after
+    /// [`usize::checked_add_signed`].
     #[inline]
-    pub const fn as_mut_ptr(self) -> *mut u8 {
-        self.0 as *mut u8
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_add_signed(self, rhs: isize) -> Option<Self> {
+        usize::checked_add_signed(self.into(), rhs).map(Self::from)
     }
 
-    /// Converts the virtual address to a mutable raw pointer of a specific
-    /// type.
+    /// Adds a given **signed** 64-bit offset to the address to get a new address.
+    ///
+    /// Unlike `checked_add_signed`, the offset may exceed the range of `isize`, e.g., when
+    /// modeling 64-bit guests on 32-bit hosts. Returns `None` if the result is not representable
+    /// by `usize`.
     #[inline]
-    pub const fn as_mut_ptr_of<T>(self) -> *mut T {
-        self.0 as *mut T
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_offset_i64(self, offset: i64) -> Option<Self> {
+        let addr = self.into() as i128 + offset as i128;
+        usize::try_from(addr).ok().map(Self::from)
     }
-}
 
-/// Alias for [`PhysAddr::from_usize`].
-#[macro_export]
-macro_rules! pa {
-    ($addr:expr) => {
-        $crate::PhysAddr::from_usize($addr)
+    /// Gets the distance between two addresses.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the result is not representable by `isize`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn offset_from(self, base: Self) -> isize {
+        match self.overflowing_offset_from(base) {
+            (_, true) => panic!(
+                "overflow in `MemoryAddr::offset_from`: {:#x} - {:#x}",
+                self.into(),
+                base.into()
+            ),
+            (result, false) => result,
+        }
+    }
+
+    /// Gets the distance between two addresses.
+    /// 
+    /// Unlike `offset_from`, this method always wraps around on overflow.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn wrapping_offset_from(self, base: Self) -> isize {
+        usize::wrapping_sub(self.into(), base.into()) as isize
+    }
+
+    /// Gets the distance between two addresses.
+    /// 
+    /// Unlike `offset_from`, this method returns a tuple of the wrapped distance and a boolean
+    /// indicating whether the distance is not representable by `isize`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn overflowing_offset_from(self, base: Self) -> (isize, bool) {
+        let result = usize::wrapping_sub(self.into(), base.into()) as isize;
+        // The result has overflowed iff its sign disagrees with the order of the addresses.
+        (result, (result > 0) ^ (base < self))
+    }
+
+    /// Gets the distance between two addresses.
+    /// 
+    /// Unlike `offset_from`, this method returns `None` if the distance is not representable
+    /// by `isize`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_offset_from(self, base: Self) -> Option<isize> {
+        match self.overflowing_offset_from(base) {
+            (_, true) => None,
+            (result, false) => Some(result),
+        }
+    }
+
+    /// Gets the distance between two addresses.
+    /// 
+    /// Unlike `offset_from`, this method saturates at `isize::MIN` or `isize::MAX` if the
+    /// distance is not representable by `isize`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn saturating_offset_from(self, base: Self) -> isize {
+        match self.overflowing_offset_from(base) {
+            (_, true) if base < self => isize::MAX,
+            (_, true) => isize::MIN,
+            (result, false) => result,
+        }
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    /// 
+    /// This method is similar to `offset`, but it takes an unsigned offset.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the result overflows. The panic message includes both operands.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn add(self, rhs: usize) -> Self {
+        let addr = self.into();
+        match usize::checked_add(addr, rhs) {
+            Some(addr) => Self::from(addr),
+            None => panic!("overflow in `MemoryAddr::add`: {:#x} + {:#x}", addr, rhs),
+        }
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    /// 
+    /// Unlike `add`, this method always wraps around on overflow.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn wrapping_add(self, rhs: usize) -> Self {
+        Self::from(usize::wrapping_add(self.into(), rhs))
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    /// 
+    /// Unlike `add`, this method returns a tuple of the new address and a boolean indicating
+    /// whether the addition has overflowed.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn overflowing_add(self, rhs: usize) -> (Self, bool) {
+        let (result, overflow) = self.into().overflowing_add(rhs);
+        (Self::from(result), overflow)
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    /// 
+    /// Unlike `add`, this method returns `None` on overflow.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_add(self, rhs: usize) -> Option<Self> {
+        usize::checked_add(self.into(), rhs).map(Self::from)
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    ///
+    /// Unlike `checked_add`, this method tells by how much the addition overflowed: it returns
+    /// `Err(excess)` on overflow, where `excess` is the wrapped-around result, i.e.,
+    /// `self + rhs - 2^usize::BITS`.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn add_reporting_overflow(self, rhs: usize) -> Result<Self, usize> {
+        match self.into().overflowing_add(rhs) {
+            (result, false) => Ok(Self::from(result)),
+            (excess, true) => Err(excess),
+        }
+    }
+
+    /// Adds a given **unsigned** offset to the address to get a new address.
+    /// 
+    /// Unlike `add`, this method saturates at the maximum address on overflow.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn saturating_add(self, rhs: usize) -> Self {
+        Self::from(usize::saturating_add(self.into(), rhs))
+    }
+
+    /// Adds `index * scale` to the address to get a new address, e.g., the address of the
+    /// `index`-th element of size `scale` in an array starting at the address.
+    /// 
+    /// Returns `None` if either the multiplication or the addition overflows.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_add_scaled(self, index: usize, scale: usize) -> Option<Self> {
+        usize::checked_mul(index, scale).and_then(|offset| self.checked_add(offset))
+    }
+
+    /// Gets the index of the element at the address in an array of elements of size `scale`
+    /// starting at `base`, i.e., `(self - base) / scale`.
+    /// 
+    /// It's the inverse of `checked_add_scaled`.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the address is below `base`, `scale` is zero, or the distance between them is
+    /// not a multiple of `scale`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn index_of(self, base: Self, scale: usize) -> usize {
+        let offset = self
+            .checked_sub_addr(base)
+            .expect("address below the base in `MemoryAddr::index_of`");
+        assert!(scale != 0, "zero scale in `MemoryAddr::index_of`");
+        assert!(offset.is_multiple_of(scale), "misaligned address in `MemoryAddr::index_of`");
+        offset / scale
+    }
+
+    /// Gets the index of the element at the address in an array of elements of size `scale`
+    /// starting at `base`, i.e., `(self - base) / scale`.
+    /// 
+    /// Unlike `index_of`, this method returns `None` if the address is below `base`, `scale` is
+    /// zero, or the distance between them is not a multiple of `scale`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_index_of(self, base: Self, scale: usize) -> Option<usize> {
+        let offset = self.checked_sub_addr(base)?;
+        match (offset.checked_div(scale), offset.checked_rem(scale)) {
+            (Some(index), Some(0)) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Subtracts a given **unsigned** offset from the address to get a new address.
+    /// 
+    /// This method is similar to `offset(-rhs)`, but it takes an unsigned offset. 
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the result overflows. The panic message includes both operands.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn sub(self, rhs: usize) -> Self {
+        let addr = self.into();
+        match usize::checked_sub(addr, rhs) {
+            Some(addr) => Self::from(addr),
+            None => panic!("overflow in `MemoryAddr::sub`: {:#x} - {:#x}", addr, rhs),
+        }
+    }
+
+    /// Subtracts a given **unsigned** offset from the address to get a new address.
+    /// 
+    /// Unlike `sub`, this method always wraps around on overflowed.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn wrapping_sub(self, rhs: usize) -> Self {
+        Self::from(usize::wrapping_sub(self.into(), rhs))
+    }
+
+    /// Subtracts a given **unsigned** offset from the address to get a new address.
+    /// 
+    /// Unlike `sub`, this method returns a tuple of the new address and a boolean indicating
+    /// whether the subtraction has overflowed.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn overflowing_sub(self, rhs: usize) -> (Self, bool) {
+        let (result, overflow) = self.into().overflowing_sub(rhs);
+        (Self::from(result), overflow)
+    }
+
+    /// Subtracts a given **unsigned** offset from the address to get a new address.
+    /// 
+    /// Unlike `sub`, this method returns `None` on overflow. See also `checked_sub_size` for
+    /// deriving the start of a region from its end and size.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_sub(self, rhs: usize) -> Option<Self> {
+        usize::checked_sub(self.into(), rhs).map(Self::from)
+    }
+
+    /// Subtracts a given **unsigned** offset from the address to get a new address.
+    /// 
+    /// Unlike `sub`, this method saturates at zero on underflow.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn saturating_sub(self, rhs: usize) -> Self {
+        Self::from(usize::saturating_sub(self.into(), rhs))
+    }
+
+    /// Subtracts another address from the address to get the offset between them.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the result overflows. The panic message includes both operands.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn sub_addr(self, rhs: Self) -> usize {
+        let (addr, rhs) = (self.into(), rhs.into());
+        match usize::checked_sub(addr, rhs) {
+            Some(offset) => offset,
+            None => panic!("overflow in `MemoryAddr::sub_addr`: {:#x} - {:#x}", addr, rhs),
+        }
+    }
+
+    /// Subtracts another address from the address to get the offset between them.
+    /// 
+    /// Unlike `sub_addr`, this method always wraps around on overflow. The wrapped distance is
+    /// meaningful in ring-buffer-style address spaces, where it's the distance from `rhs` to the
+    /// address going upwards through `usize::MAX` and zero.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use memory_addr::{va, MemoryAddr};
+    /// 
+    /// assert_eq!(va!(0x3000).wrapping_sub_addr(va!(0x1000)), 0x2000);
+    /// assert_eq!(va!(0x1000).wrapping_sub_addr(va!(usize::MAX - 0xfff)), 0x2000);
+    /// ```
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn wrapping_sub_addr(self, rhs: Self) -> usize {
+        usize::wrapping_sub(self.into(), rhs.into())
+    }
+
+    /// Subtracts another address from the address to get the offset between them.
+    /// 
+    /// Unlike `sub_addr`, this method returns a tuple of the offset and a boolean indicating
+    /// whether the subtraction has overflowed.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn overflowing_sub_addr(self, rhs: Self) -> (usize, bool) {
+        usize::overflowing_sub(self.into(), rhs.into())
+    }
+
+    /// Subtracts another address from the address to get the offset between them.
+    /// 
+    /// Unlike `sub_addr`, this method returns `None` on overflow.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_sub_addr(self, rhs: Self) -> Option<usize> {
+        usize::checked_sub(self.into(), rhs.into())
+    }
+
+    /// Subtracts another address from the address to get the offset between them.
+    /// 
+    /// Unlike `sub_addr`, this method returns zero if `rhs` is greater than the address.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn saturating_sub_addr(self, rhs: Self) -> usize {
+        usize::saturating_sub(self.into(), rhs.into())
+    }
+
+    /// Gets the absolute distance between two addresses, regardless of which one is greater.
+    /// 
+    /// This method never panics.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn abs_diff(self, other: Self) -> usize {
+        usize::abs_diff(self.into(), other.into())
+    }
+
+    /// Gets the midpoint of two addresses, rounded towards the lower one.
+    ///
+    /// It's computed as `low + (high - low) / 2`, so it never overflows, which makes it suitable
+    /// for binary searches over address ranges. The order of the two addresses doesn't matter.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn midpoint(self, other: Self) -> Self {
+        let (low, high) = if self <= other { (self, other) } else { (other, self) };
+        low.wrapping_add(high.sub_addr(low) / 2)
+    }
+
+    /// Gets the offset of the address within the region starting at `region_start`.
+    /// 
+    /// It's the same as `self.sub_addr(region_start)`, but it documents the intent.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the address is below `region_start`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn offset_into(self, region_start: Self) -> usize {
+        self.checked_offset_into(region_start)
+            .expect("address below the region start in `MemoryAddr::offset_into`")
+    }
+
+    /// Gets the offset of the address within the region starting at `region_start`.
+    /// 
+    /// Unlike `offset_into`, this method returns `None` if the address is below `region_start`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_offset_into(self, region_start: Self) -> Option<usize> {
+        self.checked_sub_addr(region_start)
+    }
+
+    /// Converts the address to a [`Wrapping<usize>`](core::num::Wrapping), for a series of
+    /// modular arithmetic operations.
+    /// 
+    /// Use `from_wrapping` to convert the result back.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn as_wrapping(self) -> core::num::Wrapping<usize> {
+        core::num::Wrapping(self.into())
+    }
+
+    /// Converts a [`Wrapping<usize>`](core::num::Wrapping) back to an address.
+    #[inline]
+    #[must_use]
+    fn from_wrapping(addr: core::num::Wrapping<usize>) -> Self {
+        Self::from(addr.0)
+    }
+
+    //
+    // This section contains utility methods for address comparison.
+    //
+
+    /// Restricts the address to the inclusive interval `[min, max]`.
+    ///
+    /// This method is equivalent to [`Ord::clamp`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn clamp_addr(self, min: Self, max: Self) -> Self {
+        self.clamp(min, max)
+    }
+
+    /// Checks whether the address is in the half-open interval `[start, end)`, i.e.,
+    /// `start <= self && self < end`.
+    ///
+    /// It always returns `false` if `start >= end`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn is_in_range(self, start: Self, end: Self) -> bool {
+        start <= self && self < end
+    }
+
+    //
+    // This section contains utility methods for address iteration.
+    //
+
+    /// Returns an iterator over the start addresses of the pages in
+    /// `[self, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is not a power of two, `self` or `end` is not
+    /// aligned to `page_size`, or `self > end`.
+    #[inline]
+    fn iter_pages(self, end: Self, page_size: usize) -> impl Iterator<Item = Self> {
+        assert!(page_size.is_power_of_two(), "page size must be a power of two: {:#x}", page_size);
+        assert!(self.is_aligned(page_size), "start is not page-aligned: {:#x}", self.into());
+        assert!(end.is_aligned(page_size), "end is not page-aligned: {:#x}", end.into());
+        assert!(self <= end, "invalid page range: {:#x}..{:#x}", self.into(), end.into());
+        (self.into()..end.into()).step_by(page_size).map(Self::from)
+    }
+
+    /// Returns an iterator over the start addresses of the 4K pages in
+    /// `[self, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or `end` is not 4K-aligned, or `self > end`.
+    #[inline]
+    fn iter_pages_4k(self, end: Self) -> impl Iterator<Item = Self> {
+        self.iter_pages(end, crate::PAGE_SIZE_4K)
+    }
+
+    //
+    // This section contains utility methods for address ranges.
+    //
+
+    /// Returns the address range `[self, self + size)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self + size` overflows.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn range(self, size: usize) -> crate::AddrRange<Self> {
+        crate::AddrRange::new(self, self.add(size))
+    }
+
+    /// Returns the address range `[self, end)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end < self`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn range_to(self, end: Self) -> crate::AddrRange<Self> {
+        crate::AddrRange::new(self, end)
+    }
+
+    /// Returns the address range `[self, end)`, or `None` if `end < self`.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_range_to(self, end: Self) -> Option<crate::AddrRange<Self>> {
+        crate::AddrRange::try_new(self, end)
+    }
+
+    /// Gets the start address of the region of `size` bytes that ends at the
+    /// address, i.e., `self - size`.
+    ///
+    /// It's the same as `checked_sub`, but it documents the intent of deriving
+    /// the start of a region from its end and size. Returns `None` if `size` is
+    /// greater than the address.
+    #[inline]
+    #[must_use = "this returns a new address, without modifying the original"]
+    fn checked_sub_size(self, size: usize) -> Option<Self> {
+        self.checked_sub(size)
+    }
+
+    /// Gets the page-aligned range `[start, end)` covering the region of `size`
+    /// bytes starting at the address, i.e., the pages touched by the region,
+    /// e.g., for flushing TLB entries.
+    ///
+    /// `start` is the address aligned down and `end` is `self + size` aligned
+    /// up to `page_size`, which must be a power of two (checked in debug
+    /// builds). An empty region covers no pages, unless the address is not
+    /// aligned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end` overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{va, MemoryAddr};
+    ///
+    /// let (start, end) = va!(0x1ff0).covering_pages(0x20, 0x1000usize);
+    /// assert_eq!((start, end), (va!(0x1000), va!(0x3000)));
+    /// ```
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn covering_pages<U>(self, size: usize, page_size: U) -> (Self, Self)
+    where
+        U: Into<usize>,
+    {
+        let page_size = page_size.into();
+        let start = self.align_down(page_size);
+        match self
+            .checked_add(size)
+            .and_then(|end| end.checked_align_up(page_size))
+        {
+            Some(end) => (start, end),
+            None => panic!(
+                "overflow in `MemoryAddr::covering_pages`: {:#x} + {:#x}",
+                self.into(),
+                size
+            ),
+        }
+    }
+
+    /// Gets the page-aligned range `[start, end)` covering the region of `size`
+    /// bytes starting at the address.
+    ///
+    /// Unlike `covering_pages`, this method returns `None` if `page_size` is
+    /// not a power of two, or if `end` overflows, e.g., for a region at the top
+    /// of the address space.
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn checked_covering_pages<U>(self, size: usize, page_size: U) -> Option<(Self, Self)>
+    where
+        U: Into<usize>,
+    {
+        let page_size = page_size.into();
+        let start = self.checked_align_down(page_size)?;
+        let end = self.checked_add(size)?.checked_align_up(page_size)?;
+        Some((start, end))
+    }
+
+    /// Packs the address as a base and a small length into a single `usize`, e.g., for compact
+    /// storage of page-aligned ranges.
+    ///
+    /// The low `len_bits` bits of the result hold `len`, and the other bits hold the address,
+    /// which must be aligned to `1 << len_bits` so that its low `len_bits` bits are free. Use
+    /// `unpack_base_len` to get the address and the length back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address is not aligned to `1 << len_bits`, if `len` doesn't fit in
+    /// `len_bits` bits, or if `len_bits` is not less than `usize::BITS`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{va, MemoryAddr, VirtAddr};
+    ///
+    /// let packed = va!(0x1234_5000).pack_base_len(3, 12);
+    /// assert_eq!(packed, 0x1234_5003);
+    /// assert_eq!(VirtAddr::unpack_base_len(packed, 12), (va!(0x1234_5000), 3));
+    /// ```
+    #[inline]
+    #[must_use = "this function has no side effects, so it can be removed if the return value is not used"]
+    fn pack_base_len(self, len: usize, len_bits: u32) -> usize {
+        let mask = shift_mask(len_bits, "pack_base_len");
+        let base = self.into();
+        assert!(base & mask == 0, "base not aligned in `MemoryAddr::pack_base_len`");
+        assert!(len <= mask, "length too large in `MemoryAddr::pack_base_len`");
+        base | len
+    }
+
+    /// Unpacks a `usize` packed by `pack_base_len` into the address and the length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len_bits` is not less than `usize::BITS`.
+    #[inline]
+    #[must_use]
+    fn unpack_base_len(packed: usize, len_bits: u32) -> (Self, usize) {
+        let mask = shift_mask(len_bits, "unpack_base_len");
+        (Self::from(packed & !mask), packed & mask)
+    }
+}
+
+/// Implement the `MemoryAddr` trait for any type that is `Copy`, `From<usize>`,
+/// `Into<usize>`, and `Ord`.
+impl<T> MemoryAddr for T where T: Copy + From<usize> + Into<usize> + Ord {}
+
+/// The error returned by [`MemoryAddr::try_align_up`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlignError {
+    /// The alignment is not a power of two (including zero).
+    NotPowerOfTwo,
+    /// The aligned address overflows.
+    Overflow,
+}
+
+impl fmt::Display for AlignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotPowerOfTwo => f.write_str("alignment is not a power of two"),
+            Self::Overflow => f.write_str("aligned address overflows"),
+        }
+    }
+}
+
+impl core::error::Error for AlignError {}
+
+/// Returns the mask `(1 << shift) - 1` for the `*_shift` and `*_base_len`
+/// methods of [`MemoryAddr`], panicking with the method name if `shift` is too
+/// large.
+#[inline]
+fn shift_mask(shift: u32, method: &str) -> usize {
+    match 1usize.checked_shl(shift) {
+        Some(align) => align - 1,
+        None => panic!("shift too large in `MemoryAddr::{}`", method),
+    }
+}
+
+/// Creates a new address type by wrapping an `usize`.
+///
+/// For each `$vis type $name;`, this macro generates the following items:
+/// - Definition of the new address type `$name`, which contains a single
+///   private unnamed field of type `usize`. It's `#[repr(transparent)]`, so
+///   it's guaranteed to have the same size, alignment, and ABI as `usize`, and
+///   can be used in FFI structs shared with C in place of `uintptr_t`. This is
+///   also checked at compile time.
+/// - Default implementations (i.e. derived implementations) for the following
+///   traits:
+///   - `Copy`, `Clone`, and
+///   - `Ord`, `PartialOrd`, `Eq`, and `PartialEq`.
+/// - A constant `DEFAULT`, which is the address zero unless specified (see
+///   below), and an implementation of `Default` returning it.
+/// - Implementations for the following traits:
+///   - `From<usize>`, `Into<usize>` (by implementing `From<$name> for usize`),
+///   - `From<NonZeroUsize>`,
+///   - `TryFrom<$name> for u32`, which fails if the address exceeds `u32::MAX`,
+///     e.g., for 32-bit hardware registers,
+///   - `Add<usize>`, `AddAssign<usize>`, `Sub<usize>`, `SubAssign<usize>`,
+///   - `Sub<$name>`,
+///   - `BitAnd<usize>`, `BitAndAssign<usize>`, `BitOr<usize>`,
+///     `BitOrAssign<usize>`, `BitXor<usize>`, `BitXorAssign<usize>`, and
+///   - `Not`.
+/// - Three `const` methods to convert between the address type and `usize`:
+///   - `from_usize`, which converts an `usize` to the address type,
+///   - `try_from_usize`, which converts an `usize` to the address type if it's
+///     valid (see below), and
+///   - `as_usize`, which converts the address type to an `usize`.
+/// - Two methods to access the `usize` inside the address type by reference:
+///   - `as_usize_ref`, which returns a shared reference, and
+///   - `as_usize_mut`, which returns a mutable reference.
+/// - Two `const` methods to view slices of the address type as slices of
+///   `usize` and vice versa without copying:
+///   - `slice_as_usize`, which converts `&[$name]` to `&[usize]`, and
+///   - `slice_from_usize`, which converts `&[usize]` to `&[$name]`.
+/// - `const` comparison methods `lt_const`, `le_const`, `gt_const`, `ge_const`,
+///   and `eq_const`, since the methods of `PartialOrd` and `PartialEq` can't be
+///   called in `const` contexts.
+/// - Two `const` methods to manipulate the bits of the address:
+///   - `set_bits`, which replaces the bits selected by a mask, and
+///   - `clear_bits`, which clears the bits selected by a mask.
+/// - `const` methods to convert between the address type and its byte
+///   representation, e.g., when parsing addresses from raw buffers:
+///   - `from_le_bytes` and `from_be_bytes`, which convert from byte arrays in
+///     little and big endian, respectively, and
+///   - `to_le_bytes` and `to_be_bytes`, which convert to byte arrays in little
+///     and big endian, respectively.
+/// - An implementation of `arbitrary::Arbitrary`, which draws an arbitrary
+///   `usize`, if the `arbitrary` feature is enabled.
+/// - An implementation of `num_traits::Bounded`, which ranges from `0` to
+///   `usize::MAX`, if the `num-traits` feature is enabled.
+/// - Implementations of `bytemuck::Zeroable` and `bytemuck::Pod` if the
+///   `bytemuck` feature is enabled.
+///
+/// `Debug` is also implemented, which formats the address in hexadecimal with
+/// the type name as the prefix, e.g., `ExampleAddr(0x1abc)`. To implement it
+/// with [`def_usize_addr_formatter`](crate::def_usize_addr_formatter) or by
+/// hand instead, mark the type with `#[custom_debug]`, which opts out of the
+/// default implementation and is not passed on to the struct. It must not be
+/// derived, as it would conflict with the default implementation.
+///
+/// The arithmetic operators behave like those of `usize`, i.e., they panic on
+/// overflow in debug builds. Use the methods of [`MemoryAddr`] for other
+/// behaviors on overflow, e.g., [`saturating_sub`](MemoryAddr::saturating_sub)
+/// or [`checked_sub`](MemoryAddr::checked_sub).
+///
+/// The width of the address type can be given with `$vis type $name: $bits
+/// bits;`, for address spaces narrower than `usize` (e.g., 39-bit addresses
+/// of RISC-V Sv39). Then the following items are also generated:
+/// - Two constants `ADDR_BITS` and `MASK`, which are the width and the mask of
+///   the significant bits, respectively, and
+/// - `truncate`, which clears the bits above the width.
+///
+/// Besides, `from_usize` checks that the address fits in the width in debug
+/// builds, and `try_from_usize` returns `None` for addresses that don't.
+///
+/// The address type can be branded with an address space with `$vis type
+/// $name in $space;`, where `$space` is the path of a type implementing
+/// [`AddrSpace`](crate::AddrSpace). Then [`HasAddrSpace`](crate::HasAddrSpace)
+/// is implemented for it, so that generic code can require multiple address
+/// types to belong to the same address space. If the width is also given, it
+/// goes first, followed by the address space and then the validation
+/// predicate.
+///
+/// A validation predicate can be attached with `$vis type $name where
+/// $check;`, where `$check` is the path of a `const fn(usize) -> bool`. Then
+/// `try_from_usize` returns `None` for addresses rejected by it. Without the
+/// predicate, `try_from_usize` always succeeds. Other constructors (e.g.
+/// `from_usize` and `From<usize>`) never validate the address.
+///
+/// A default value other than zero can be given with `$vis type $name =
+/// $default;`, where `$default` is a constant expression of type `usize`, e.g.,
+/// `usize::MAX` as a sentinel meaning "unset". It goes last if combined with
+/// the other options, and it's checked at compile time to fit in the width and
+/// pass the validation predicate, if any.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{def_usize_addr, MemoryAddr};
+///
+/// def_usize_addr! {
+///     /// A example address type.
+///     pub type ExampleAddr;
+/// }
+///
+/// # fn main() {
+/// const EXAMPLE: ExampleAddr = ExampleAddr::from_usize(0x1234);
+/// const EXAMPLE_USIZE: usize = EXAMPLE.as_usize();
+/// assert_eq!(EXAMPLE_USIZE, 0x1234);
+/// assert_eq!(EXAMPLE.align_down(0x10usize), ExampleAddr::from_usize(0x1230));
+/// assert_eq!(EXAMPLE.align_up_4k(), ExampleAddr::from_usize(0x2000));
+/// assert_eq!(format!("{:?}", EXAMPLE), "ExampleAddr(0x1234)");
+///
+/// const END: ExampleAddr = ExampleAddr::from_usize(0x2000);
+/// const _: () = assert!(EXAMPLE.lt_const(END) && !EXAMPLE.eq_const(END));
+/// # }
+/// ```
+///
+/// With a validation predicate:
+///
+/// ```
+/// use memory_addr::def_usize_addr;
+///
+/// const fn is_user(addr: usize) -> bool {
+///     addr < 0x8000_0000
+/// }
+///
+/// def_usize_addr! {
+///     /// A user space address type.
+///     pub type UserAddr where is_user;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(UserAddr::try_from_usize(0x1000), Some(UserAddr::from_usize(0x1000)));
+/// assert_eq!(UserAddr::try_from_usize(0x8000_0000), None);
+/// # }
+/// ```
+///
+/// With a default value:
+///
+/// ```
+/// use memory_addr::def_usize_addr;
+///
+/// def_usize_addr! {
+///     /// An address type whose default value means "unset".
+///     pub type EntryAddr = usize::MAX;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(EntryAddr::default(), EntryAddr::from_usize(usize::MAX));
+/// assert_eq!(EntryAddr::DEFAULT, EntryAddr::default());
+/// # }
+/// ```
+///
+/// And this won't compile, since the default value exceeds the width:
+///
+/// ```compile_fail
+/// use memory_addr::def_usize_addr;
+///
+/// def_usize_addr! {
+///     /// An address type with an invalid default value.
+///     pub type NarrowAddr: 16 bits = 0x1_0000;
+/// }
+/// ```
+///
+/// With a width:
+///
+/// ```
+/// use memory_addr::def_usize_addr;
+///
+/// def_usize_addr! {
+///     /// A RISC-V Sv39 virtual address type.
+///     pub type Sv39Addr: 39 bits;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Sv39Addr::MASK, 0x7f_ffff_ffff);
+/// assert_eq!(Sv39Addr::try_from_usize(0x80_0000_0000), None);
+/// assert_eq!(Sv39Addr::from(0xffff_ffc0_0000_1000).truncate(), Sv39Addr::from_usize(0x40_0000_1000));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_usize_addr {
+    (
+        @attrs [$($attrs:tt)*] [$($debug:ident)?]
+        #[custom_debug]
+        $($tt:tt)*
+    ) => {
+        $crate::def_usize_addr!(@attrs [$($attrs)*] [] $($tt)*);
+    };
+    (
+        @attrs [$($attrs:tt)*] [$($debug:ident)?]
+        #[$meta:meta]
+        $($tt:tt)*
+    ) => {
+        $crate::def_usize_addr!(@attrs [$($attrs)* #[$meta]] [$($debug)?] $($tt)*);
+    };
+    (
+        @attrs [$(#[$meta:meta])*] [$($debug:ident)?]
+        $vis:vis type $name:ident $(: $bits:literal bits)? $(in $space:path)? $(where $check:path)? $(= $default:expr)?;
+
+        $($tt:tt)*
+    ) => {
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+        $(#[$meta])*
+        pub struct $name(usize);
+
+        const _: () = assert!(
+            core::mem::size_of::<$name>() == core::mem::size_of::<usize>()
+                && core::mem::align_of::<$name>() == core::mem::align_of::<usize>()
+        );
+
+        impl $name {
+            #[doc = concat!("The default value of [`", stringify!($name), "`], usable in `const` contexts.")]
+            pub const DEFAULT: Self = Self($crate::__addr_default_value!($($default)?));
+
+            #[doc = concat!("Converts an `usize` to an [`", stringify!($name), "`].")]
+            #[inline]
+            pub const fn from_usize(addr: usize) -> Self {
+                $(
+                    debug_assert!(
+                        addr <= usize::MAX >> (usize::BITS - $bits),
+                        concat!("address exceeds the width of `", stringify!($name), "`"),
+                    );
+                )?
+                Self(addr)
+            }
+
+            #[doc = concat!("Converts an `usize` to an [`", stringify!($name), "`], returning `None` if the address is invalid.")]
+            #[inline]
+            pub const fn try_from_usize(addr: usize) -> Option<Self> {
+                $(
+                    if addr > usize::MAX >> (usize::BITS - $bits) {
+                        return None;
+                    }
+                )?
+                $(
+                    if !$check(addr) {
+                        return None;
+                    }
+                )?
+                Some(Self(addr))
+            }
+
+            #[doc = concat!("Converts an [`", stringify!($name), "`] to an `usize`.")]
+            #[inline]
+            pub const fn as_usize(self) -> usize {
+                self.0
+            }
+
+            #[doc = concat!("Returns a reference to the `usize` inside the [`", stringify!($name), "`].")]
+            #[inline]
+            pub const fn as_usize_ref(&self) -> &usize {
+                &self.0
+            }
+
+            #[doc = concat!("Returns a mutable reference to the `usize` inside the [`", stringify!($name), "`].")]
+            ///
+            /// It's useful for modifying the address in place, e.g.,
+            /// `*addr.as_usize_mut() |= flag`.
+            #[inline]
+            pub fn as_usize_mut(&mut self) -> &mut usize {
+                &mut self.0
+            }
+
+            #[doc = concat!("Views a slice of [`", stringify!($name), "`]s as a slice of `usize`s without copying.")]
+            #[inline]
+            pub const fn slice_as_usize(addrs: &[Self]) -> &[usize] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over `usize`, so
+                // they have the same size, alignment, and validity.
+                unsafe { core::slice::from_raw_parts(addrs.as_ptr() as *const usize, addrs.len()) }
+            }
+
+            #[doc = concat!("Views a slice of `usize`s as a slice of [`", stringify!($name), "`]s without copying.")]
+            #[inline]
+            pub const fn slice_from_usize(addrs: &[usize]) -> &[Self] {
+                // SAFETY: `Self` is `#[repr(transparent)]` over `usize`, so
+                // they have the same size, alignment, and validity.
+                unsafe { core::slice::from_raw_parts(addrs.as_ptr() as *const Self, addrs.len()) }
+            }
+
+            /// Checks whether `self < other`, usable in `const` contexts.
+            #[inline]
+            pub const fn lt_const(self, other: Self) -> bool {
+                self.0 < other.0
+            }
+
+            /// Checks whether `self <= other`, usable in `const` contexts.
+            #[inline]
+            pub const fn le_const(self, other: Self) -> bool {
+                self.0 <= other.0
+            }
+
+            /// Checks whether `self > other`, usable in `const` contexts.
+            #[inline]
+            pub const fn gt_const(self, other: Self) -> bool {
+                self.0 > other.0
+            }
+
+            /// Checks whether `self >= other`, usable in `const` contexts.
+            #[inline]
+            pub const fn ge_const(self, other: Self) -> bool {
+                self.0 >= other.0
+            }
+
+            /// Checks whether `self == other`, usable in `const` contexts.
+            #[inline]
+            pub const fn eq_const(self, other: Self) -> bool {
+                self.0 == other.0
+            }
+
+            /// Replaces the bits selected by `mask` with the corresponding bits
+            /// of `value`, leaving other bits unchanged.
+            #[inline]
+            #[must_use = "this returns a new address, without modifying the original"]
+            pub const fn set_bits(self, mask: usize, value: usize) -> Self {
+                Self((self.0 & !mask) | (value & mask))
+            }
+
+            /// Clears the bits selected by `mask`, leaving other bits unchanged.
+            #[inline]
+            #[must_use = "this returns a new address, without modifying the original"]
+            pub const fn clear_bits(self, mask: usize) -> Self {
+                Self(self.0 & !mask)
+            }
+
+            #[doc = concat!("Creates an [`", stringify!($name), "`] from its representation as a byte array in little endian.")]
+            #[inline]
+            pub const fn from_le_bytes(bytes: [u8; core::mem::size_of::<usize>()]) -> Self {
+                Self(usize::from_le_bytes(bytes))
+            }
+
+            #[doc = concat!("Creates an [`", stringify!($name), "`] from its representation as a byte array in big endian.")]
+            #[inline]
+            pub const fn from_be_bytes(bytes: [u8; core::mem::size_of::<usize>()]) -> Self {
+                Self(usize::from_be_bytes(bytes))
+            }
+
+            /// Returns the memory representation of the address as a byte
+            /// array in little endian.
+            #[inline]
+            pub const fn to_le_bytes(self) -> [u8; core::mem::size_of::<usize>()] {
+                self.0.to_le_bytes()
+            }
+
+            /// Returns the memory representation of the address as a byte
+            /// array in big endian.
+            #[inline]
+            pub const fn to_be_bytes(self) -> [u8; core::mem::size_of::<usize>()] {
+                self.0.to_be_bytes()
+            }
+        }
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::DEFAULT
+            }
+        }
+
+        $(
+            const _: () = assert!(
+                $name::try_from_usize($default).is_some(),
+                concat!("invalid default value of `", stringify!($name), "`"),
+            );
+        )?
+
+        impl From<usize> for $name {
+            #[inline]
+            fn from(addr: usize) -> Self {
+                Self(addr)
+            }
+        }
+
+        impl From<$name> for usize {
+            #[inline]
+            fn from(addr: $name) -> usize {
+                addr.0
+            }
+        }
+
+        impl From<core::num::NonZeroUsize> for $name {
+            #[inline]
+            fn from(addr: core::num::NonZeroUsize) -> Self {
+                Self(addr.get())
+            }
+        }
+
+        impl TryFrom<$name> for u32 {
+            type Error = core::num::TryFromIntError;
+
+            #[inline]
+            fn try_from(addr: $name) -> Result<u32, Self::Error> {
+                u32::try_from(addr.0)
+            }
+        }
+
+        impl core::ops::Add<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn add(self, rhs: usize) -> Self {
+                Self(self.0 + rhs)
+            }
+        }
+
+        impl core::ops::AddAssign<usize> for $name {
+            #[inline]
+            fn add_assign(&mut self, rhs: usize) {
+                self.0 += rhs;
+            }
+        }
+
+        impl core::ops::Sub<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn sub(self, rhs: usize) -> Self {
+                Self(self.0 - rhs)
+            }
+        }
+
+        impl core::ops::SubAssign<usize> for $name {
+            #[inline]
+            fn sub_assign(&mut self, rhs: usize) {
+                self.0 -= rhs;
+            }
+        }
+
+        impl core::ops::Sub<$name> for $name {
+            type Output = usize;
+            #[inline]
+            fn sub(self, rhs: $name) -> usize {
+                self.0 - rhs.0
+            }
+        }
+
+        impl core::ops::BitAnd<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn bitand(self, rhs: usize) -> Self {
+                Self(self.0 & rhs)
+            }
+        }
+
+        impl core::ops::BitAndAssign<usize> for $name {
+            #[inline]
+            fn bitand_assign(&mut self, rhs: usize) {
+                self.0 &= rhs;
+            }
+        }
+
+        impl core::ops::BitOr<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn bitor(self, rhs: usize) -> Self {
+                Self(self.0 | rhs)
+            }
+        }
+
+        impl core::ops::BitOrAssign<usize> for $name {
+            #[inline]
+            fn bitor_assign(&mut self, rhs: usize) {
+                self.0 |= rhs;
+            }
+        }
+
+        impl core::ops::BitXor<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn bitxor(self, rhs: usize) -> Self {
+                Self(self.0 ^ rhs)
+            }
+        }
+
+        impl core::ops::BitXorAssign<usize> for $name {
+            #[inline]
+            fn bitxor_assign(&mut self, rhs: usize) {
+                self.0 ^= rhs;
+            }
+        }
+
+        impl core::ops::Not for $name {
+            type Output = Self;
+            #[inline]
+            fn not(self) -> Self {
+                Self(!self.0)
+            }
+        }
+
+        $(
+            const _: () = assert!(
+                $bits > 0 && $bits <= usize::BITS,
+                concat!("invalid width of `", stringify!($name), "`"),
+            );
+
+            impl $name {
+                /// The number of significant bits of the address.
+                pub const ADDR_BITS: u32 = $bits;
+
+                /// The mask of the significant bits of the address.
+                pub const MASK: usize = usize::MAX >> (usize::BITS - Self::ADDR_BITS);
+
+                /// Clears the bits above [`Self::ADDR_BITS`].
+                #[inline]
+                #[must_use = "this returns a new address, without modifying the original"]
+                pub const fn truncate(self) -> Self {
+                    Self(self.0 & Self::MASK)
+                }
+            }
+        )?
+
+        $(
+            impl $crate::HasAddrSpace for $name {
+                type Space = $space;
+            }
+        )?
+
+        $crate::__impl_arbitrary_for_addr!($name);
+        $crate::__impl_num_traits_for_addr!($name);
+        $crate::__impl_bytemuck_for_addr!($name);
+
+        // `$debug` is `Debug` unless the type is marked with `#[custom_debug]`.
+        $(
+            impl core::fmt::$debug for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    f.write_fmt(format_args!(concat!(stringify!($name), "({:#x})"), self.0))
+                }
+            }
+        )?
+
+        $crate::def_usize_addr!($($tt)*);
+    };
+    () => {};
+    ($($tt:tt)+) => {
+        $crate::def_usize_addr!(@attrs [] [Debug] $($tt)+);
+    };
+}
+
+/// Expands to the default value of an address type defined by
+/// [`def_usize_addr`], which is `0` if not given.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __addr_default_value {
+    () => {
+        0
+    };
+    ($default:expr) => {
+        $default
+    };
+}
+
+/// Implements `arbitrary::Arbitrary` for an address type defined by
+/// [`def_usize_addr`], by drawing an arbitrary `usize`.
+///
+/// The feature check happens here rather than in [`def_usize_addr`], so that
+/// it's evaluated against this crate's features instead of the caller's.
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_arbitrary_for_addr {
+    ($name:ident) => {
+        impl<'a> $crate::arbitrary::Arbitrary<'a> for $name {
+            #[inline]
+            fn arbitrary(
+                u: &mut $crate::arbitrary::Unstructured<'a>,
+            ) -> $crate::arbitrary::Result<Self> {
+                <usize as $crate::arbitrary::Arbitrary<'a>>::arbitrary(u).map(Self)
+            }
+
+            #[inline]
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <usize as $crate::arbitrary::Arbitrary<'a>>::size_hint(depth)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_arbitrary_for_addr {
+    ($name:ident) => {};
+}
+
+/// Implements `num_traits::Bounded` for an address type defined by
+/// [`def_usize_addr`].
+///
+/// `Zero` and `One` are not implemented, as they require adding and
+/// multiplying two addresses, which are meaningless.
+#[cfg(feature = "num-traits")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_num_traits_for_addr {
+    ($name:ident) => {
+        impl $crate::num_traits::Bounded for $name {
+            #[inline]
+            fn min_value() -> Self {
+                Self(usize::MIN)
+            }
+
+            #[inline]
+            fn max_value() -> Self {
+                Self(usize::MAX)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "num-traits"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_num_traits_for_addr {
+    ($name:ident) => {};
+}
+
+/// Implements `bytemuck::Zeroable` and `bytemuck::Pod` for an address type
+/// defined by [`def_usize_addr`].
+#[cfg(feature = "bytemuck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_bytemuck_for_addr {
+    ($name:ident) => {
+        // SAFETY: the type is `#[repr(transparent)]` over `usize`, which is
+        // `Zeroable` and `Pod`.
+        unsafe impl $crate::bytemuck::Zeroable for $name {}
+        unsafe impl $crate::bytemuck::Pod for $name {}
+    };
+}
+
+#[cfg(not(feature = "bytemuck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_bytemuck_for_addr {
+    ($name:ident) => {};
+}
+
+/// Implements `defmt::Format` for an address type with a formatter defined by
+/// [`def_usize_addr_formatter`], which formats the address in the same way as
+/// [`core::fmt::Debug`].
+///
+/// The address is encoded as a raw `u64` with the `#x` display hint, so that
+/// the formatting is deferred to the host (`=usize` is only 32 bits wide). The
+/// parts of the format before and after the `{}` placeholder are encoded as
+/// strings.
+///
+/// The feature check happens here rather than in
+/// [`def_usize_addr_formatter`], so that it's evaluated against this crate's
+/// features instead of the caller's.
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_defmt_for_addr {
+    ($name:ident, $format:expr) => {
+        impl $crate::defmt::Format for $name {
+            fn format(&self, f: $crate::defmt::Formatter) {
+                const PARTS: (&str, &str) = $crate::__split_addr_format($format);
+                // `=usize` is truncated to 32 bits by `defmt`, so widen it.
+                let value: usize = ::core::convert::Into::into(self.0);
+                $crate::defmt::write!(f, "{=str}{=u64:#x}{=str}", PARTS.0, value as u64, PARTS.1)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_defmt_for_addr {
+    ($name:ident, $format:expr) => {};
+}
+
+/// Splits the format of [`def_usize_addr_formatter`] into the parts before
+/// and after the first `{}` placeholder, for [`__impl_defmt_for_addr`].
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub const fn __split_addr_format(format: &'static str) -> (&'static str, &'static str) {
+    let bytes = format.as_bytes();
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            let (prefix, rest) = bytes.split_at(i);
+            let (_, suffix) = rest.split_at(2);
+            // Splitting at ASCII characters keeps both parts valid UTF-8.
+            return match (core::str::from_utf8(prefix), core::str::from_utf8(suffix)) {
+                (Ok(prefix), Ok(suffix)) => (prefix, suffix),
+                _ => unreachable!(),
+            };
+        }
+        i += 1;
+    }
+    panic!("missing `{{}}` in the address format")
+}
+
+/// Creates a new address type by wrapping a [`NonZeroUsize`].
+///
+/// The address zero can't be represented by such types, so that
+/// `Option<$name>` has the same size as `$name` thanks to the null pointer
+/// optimization. It's useful for tables of optional addresses where zero is
+/// never a valid address.
+///
+/// As these types can't be converted from arbitrary `usize`s, they don't
+/// implement [`MemoryAddr`]. Convert them to `usize` or to an address type
+/// defined by [`def_usize_addr`] for address arithmetic.
+///
+/// For each `$vis type $name;`, this macro generates the following items:
+/// - Definition of the new address type `$name`, which contains a single
+///   private unnamed field of type [`NonZeroUsize`].
+/// - Default implementations (i.e. derived implementations) for the following
+///   traits:
+///   - `Copy`, `Clone`,
+///   - `Ord`, `PartialOrd`, `Eq`, and `PartialEq`.
+/// - Implementations for the following traits:
+///   - `From<NonZeroUsize>`, `TryFrom<usize>`, and
+///   - `From<$name> for usize`, `From<$name> for NonZeroUsize`.
+/// - `const` methods to convert between the address type and `usize`:
+///   - `new`, which converts an `usize` to the address type, returning `None`
+///     if it's zero,
+///   - `from_nonzero`, which converts a [`NonZeroUsize`] to the address type,
+///   - `as_usize`, which converts the address type to an `usize`, and
+///   - `as_nonzero`, which converts the address type to a [`NonZeroUsize`].
+///
+/// [`def_usize_addr_formatter`](crate::def_usize_addr_formatter) can also be
+/// used on these types.
+///
+/// [`NonZeroUsize`]: core::num::NonZeroUsize
+///
+/// # Example
+///
+/// ```
+/// use core::num::NonZeroUsize;
+/// use memory_addr::def_nonzero_addr;
+///
+/// def_nonzero_addr! {
+///     /// An example frame address type.
+///     #[derive(Debug)]
+///     pub type FrameAddr;
+/// }
+///
+/// # fn main() {
+/// const FRAME: Option<FrameAddr> = FrameAddr::new(0x1000);
+/// assert_eq!(FRAME.unwrap().as_usize(), 0x1000);
+/// assert_eq!(FrameAddr::new(0), None);
+/// assert_eq!(
+///     FrameAddr::from(NonZeroUsize::new(0x2000).unwrap()),
+///     FrameAddr::try_from(0x2000).unwrap()
+/// );
+/// assert_eq!(size_of::<Option<FrameAddr>>(), size_of::<usize>());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_nonzero_addr {
+    (
+        $(#[$meta:meta])*
+        $vis:vis type $name:ident;
+
+        $($tt:tt)*
+    ) => {
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+        $(#[$meta])*
+        pub struct $name(core::num::NonZeroUsize);
+
+        impl $name {
+            #[doc = concat!("Converts an `usize` to an [`", stringify!($name), "`], returning `None` if it's zero.")]
+            #[inline]
+            pub const fn new(addr: usize) -> Option<Self> {
+                match core::num::NonZeroUsize::new(addr) {
+                    Some(addr) => Some(Self(addr)),
+                    None => None,
+                }
+            }
+
+            #[doc = concat!("Converts a `NonZeroUsize` to an [`", stringify!($name), "`].")]
+            #[inline]
+            pub const fn from_nonzero(addr: core::num::NonZeroUsize) -> Self {
+                Self(addr)
+            }
+
+            #[doc = concat!("Converts an [`", stringify!($name), "`] to an `usize`.")]
+            #[inline]
+            pub const fn as_usize(self) -> usize {
+                self.0.get()
+            }
+
+            #[doc = concat!("Converts an [`", stringify!($name), "`] to a `NonZeroUsize`.")]
+            #[inline]
+            pub const fn as_nonzero(self) -> core::num::NonZeroUsize {
+                self.0
+            }
+        }
+
+        impl From<core::num::NonZeroUsize> for $name {
+            #[inline]
+            fn from(addr: core::num::NonZeroUsize) -> Self {
+                Self(addr)
+            }
+        }
+
+        impl TryFrom<usize> for $name {
+            type Error = core::num::TryFromIntError;
+
+            #[inline]
+            fn try_from(addr: usize) -> Result<Self, Self::Error> {
+                core::num::NonZeroUsize::try_from(addr).map(Self)
+            }
+        }
+
+        impl From<$name> for usize {
+            #[inline]
+            fn from(addr: $name) -> usize {
+                addr.0.get()
+            }
+        }
+
+        impl From<$name> for core::num::NonZeroUsize {
+            #[inline]
+            fn from(addr: $name) -> core::num::NonZeroUsize {
+                addr.0
+            }
+        }
+
+        $crate::def_nonzero_addr!($($tt)*);
+    };
+    () => {};
+}
+
+/// Creates conversions between address types defined by the
+/// [`def_usize_addr`], by forwarding the inner `usize`.
+///
+/// For each `$from => $to`, this macro generates an implementation of
+/// `From<$from> for $to` (and thus `Into<$to> for $from`). Use both
+/// `$a => $b` and `$b => $a` for conversions in both directions.
+///
+/// Note that this deliberately bypasses the type safety between address types,
+/// so it should only be used for address types that really share the same
+/// numeric space (e.g., kernel and user addresses in a single address space).
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{def_usize_addr, impl_addr_conversion};
+///
+/// def_usize_addr! {
+///     /// A kernel address type.
+///     pub type KernelAddr;
+///     /// A user address type.
+///     pub type UserAddr;
+/// }
+///
+/// impl_addr_conversion!(KernelAddr => UserAddr, UserAddr => KernelAddr);
+///
+/// # fn main() {
+/// let user: UserAddr = KernelAddr::from_usize(0x1000).into();
+/// assert_eq!(user, UserAddr::from_usize(0x1000));
+/// assert_eq!(KernelAddr::from(user), KernelAddr::from_usize(0x1000));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_addr_conversion {
+    ($($from:ty => $to:ty),+ $(,)?) => {
+        $(
+            impl From<$from> for $to {
+                #[inline]
+                fn from(addr: $from) -> Self {
+                    Self::from_usize(addr.as_usize())
+                }
+            }
+        )+
+    };
+}
+
+/// Creates methods for converting between region-relative and absolute
+/// addresses for the given address types defined by the [`def_usize_addr`].
+///
+/// It's useful for address types relative to a region, where the address zero
+/// of the type corresponds to the absolute address `$base`, e.g., the offset
+/// in a memory-mapped region.
+///
+/// For each `$name = $base;`, this macro generates the following items for
+/// the address type `$name`, where `$base` is a constant expression of type
+/// `usize`:
+/// - A constant `BASE`, which is `$base`,
+/// - A `const` method `to_absolute`, which adds `BASE` to the address and
+///   panics on overflow,
+/// - A `const` method `from_absolute`, which subtracts `BASE` from an absolute
+///   address and panics if it's below `BASE`, and
+/// - A `const` method `checked_from_absolute`, which returns `None` instead of
+///   panicking.
+///
+/// The methods `from_usize` and `as_usize` still use the raw, region-relative
+/// value.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{def_usize_addr, def_usize_addr_base};
+///
+/// def_usize_addr! {
+///     /// An address relative to the start of the DRAM.
+///     pub type DramAddr;
+/// }
+///
+/// def_usize_addr_base! {
+///     DramAddr = 0x8000_0000;
+/// }
+///
+/// # fn main() {
+/// let addr = DramAddr::from_usize(0x1000);
+/// assert_eq!(addr.to_absolute(), 0x8000_1000);
+/// assert_eq!(DramAddr::from_absolute(0x8000_1000), addr);
+/// assert_eq!(DramAddr::checked_from_absolute(0x1000), None);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_usize_addr_base {
+    (
+        $name:ident = $base:expr;
+
+        $($tt:tt)*
+    ) => {
+        impl $name {
+            /// The absolute address that the address zero corresponds to.
+            pub const BASE: usize = $base;
+
+            /// Converts the region-relative address to an absolute address,
+            /// by adding [`Self::BASE`].
+            ///
+            /// # Panics
+            ///
+            /// Panics if the result overflows.
+            #[inline]
+            pub const fn to_absolute(self) -> usize {
+                match self.0.checked_add(Self::BASE) {
+                    Some(addr) => addr,
+                    None => panic!(concat!("overflow in `", stringify!($name), "::to_absolute`")),
+                }
+            }
+
+            /// Converts an absolute address to a region-relative address, by
+            /// subtracting [`Self::BASE`].
+            ///
+            /// # Panics
+            ///
+            /// Panics if `addr` is below [`Self::BASE`].
+            #[inline]
+            pub const fn from_absolute(addr: usize) -> Self {
+                match Self::checked_from_absolute(addr) {
+                    Some(addr) => addr,
+                    None => panic!(concat!("address below the base in `", stringify!($name), "::from_absolute`")),
+                }
+            }
+
+            /// Converts an absolute address to a region-relative address, by
+            /// subtracting [`Self::BASE`].
+            ///
+            /// Returns `None` if `addr` is below [`Self::BASE`].
+            #[inline]
+            pub const fn checked_from_absolute(addr: usize) -> Option<Self> {
+                match addr.checked_sub(Self::BASE) {
+                    Some(addr) => Some(Self(addr)),
+                    None => None,
+                }
+            }
+        }
+
+        $crate::def_usize_addr_base!($($tt)*);
+    };
+    () => {};
+}
+
+/// Creates implementations for comparing the given address types defined by
+/// the [`def_usize_addr`] with `usize` values.
+///
+/// For each `$name;`, this macro generates the following items:
+/// - Implementations of `PartialEq<usize>` and `PartialOrd<usize>` for the
+///   address type `$name`, and
+/// - Implementations of `PartialEq<$name>` and `PartialOrd<$name>` for `usize`,
+///
+/// which compare the inner `usize`, so that addresses can be compared with raw
+/// constants directly, e.g., `addr == 0x1000` or `addr < 0x1000`.
+///
+/// These implementations are not generated by [`def_usize_addr`] itself,
+/// because they make comparisons with converted values like
+/// `addr == 0x1000.into()` ambiguous, which then fail to compile. With this
+/// macro, such comparisons must be written without `into`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{def_usize_addr, def_usize_addr_usize_cmp};
+///
+/// def_usize_addr! {
+///     /// An example address type.
+///     pub type ExampleAddr;
+/// }
+///
+/// def_usize_addr_usize_cmp! {
+///     ExampleAddr;
+/// }
+///
+/// # fn main() {
+/// let addr = ExampleAddr::from_usize(0x1000);
+/// assert_eq!(addr, 0x1000);
+/// assert!(addr < 0x2000);
+/// assert!(0x800 <= addr);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_usize_addr_usize_cmp {
+    (
+        $name:ident;
+
+        $($tt:tt)*
+    ) => {
+        impl PartialEq<usize> for $name {
+            #[inline]
+            fn eq(&self, other: &usize) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$name> for usize {
+            #[inline]
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialOrd<usize> for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &usize) -> Option<core::cmp::Ordering> {
+                self.0.partial_cmp(other)
+            }
+        }
+
+        impl PartialOrd<$name> for usize {
+            #[inline]
+            fn partial_cmp(&self, other: &$name) -> Option<core::cmp::Ordering> {
+                self.partial_cmp(&other.0)
+            }
+        }
+
+        $crate::def_usize_addr_usize_cmp!($($tt)*);
+    };
+    () => {};
+}
+
+/// Creates implementations for signed arithmetic operators for the given
+/// address types defined by the [`def_usize_addr`].
+///
+/// For each `$name;`, this macro generates the following items:
+/// - Implementations of `Add<isize>`, `AddAssign<isize>`, `Sub<isize>`, and
+///   `SubAssign<isize>` for the address type `$name`, which panic on overflow
+///   like [`MemoryAddr::offset`].
+///
+/// These operators are not generated by [`def_usize_addr`] itself, because
+/// having both `Add<usize>` and `Add<isize>` makes expressions with untyped
+/// integer literals like `addr + 0x1000` ambiguous, which then fail to
+/// compile. With this macro, such literals must be suffixed with `usize` or
+/// `isize`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{def_usize_addr, def_usize_addr_signed_ops};
+///
+/// def_usize_addr! {
+///     /// An example address type.
+///     pub type ExampleAddr;
+/// }
+///
+/// def_usize_addr_signed_ops! {
+///     ExampleAddr;
+/// }
+///
+/// # fn main() {
+/// let addr = ExampleAddr::from_usize(0x1000);
+/// assert_eq!(addr + (-0x10isize), ExampleAddr::from_usize(0xff0));
+/// assert_eq!(addr - (-0x10isize), ExampleAddr::from_usize(0x1010));
+/// assert_eq!(addr + 0x10usize, ExampleAddr::from_usize(0x1010));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_usize_addr_signed_ops {
+    (
+        $name:ident;
+
+        $($tt:tt)*
+    ) => {
+        impl core::ops::Add<isize> for $name {
+            type Output = Self;
+            #[inline]
+            fn add(self, rhs: isize) -> Self {
+                Self(self.0.checked_add_signed(rhs).expect(concat!("overflow in `", stringify!($name), " + isize`")))
+            }
+        }
+
+        impl core::ops::AddAssign<isize> for $name {
+            #[inline]
+            fn add_assign(&mut self, rhs: isize) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl core::ops::Sub<isize> for $name {
+            type Output = Self;
+            #[inline]
+            fn sub(self, rhs: isize) -> Self {
+                let result = if rhs >= 0 {
+                    self.0.checked_sub(rhs as usize)
+                } else {
+                    self.0.checked_add(rhs.unsigned_abs())
+                };
+                Self(result.expect(concat!("overflow in `", stringify!($name), " - isize`")))
+            }
+        }
+
+        impl core::ops::SubAssign<isize> for $name {
+            #[inline]
+            fn sub_assign(&mut self, rhs: isize) {
+                *self = *self - rhs;
+            }
+        }
+
+        $crate::def_usize_addr_signed_ops!($($tt)*);
+    };
+    () => {};
+}
+
+/// Creates implementations for the [`Debug`](core::fmt::Debug),
+/// [`LowerHex`](core::fmt::LowerHex), and [`UpperHex`](core::fmt::UpperHex)
+/// traits for the given address types defined by the [`def_usize_addr`].
+///
+/// For each `$name = $format;`, this macro generates the following items:
+/// - An implementation of [`core::fmt::Debug`] for the address type `$name`,
+///   which formats the address with `format_args!($format,
+///   format_args!("{:#x}", self.0))`,
+/// - An implementation of [`core::fmt::LowerHex`] for the address type `$name`,
+///   which formats the address in the same way as [`core::fmt::Debug`],
+/// - An implementation of [`core::fmt::UpperHex`] for the address type `$name`,
+///   which formats the address with `format_args!($format,
+///   format_args!("{:#X}", self.0))`, and
+/// - An implementation of `defmt::Format` for the address type `$name`, which
+///   formats the address in the same way as [`core::fmt::Debug`], if the
+///   `defmt` feature is enabled. The crate invoking this macro must then depend
+///   on `defmt` directly, as the `defmt` macros refer to it by name.
+///
+/// The format can be omitted with `$name;`, which is equivalent to `$name =
+/// "$name({})";`, i.e., the address is prefixed with the type name (e.g.,
+/// `ExampleAddr(0x1abc)`).
+///
+/// The address types must be marked with `#[custom_debug]` in
+/// [`def_usize_addr`], so that it doesn't implement `Debug` by itself.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{PhysAddr, VirtAddr, def_usize_addr, def_usize_addr_formatter};
+///
+/// def_usize_addr! {
+///     /// An example address type.
+///     #[custom_debug]
+///     pub type ExampleAddr;
+/// }
+///
+/// def_usize_addr! {
+///     /// Another example address type.
+///     #[custom_debug]
+///     pub type GuestPhysAddr;
+/// }
+///
+/// def_usize_addr_formatter! {
+///     ExampleAddr = "EA:{}";
+///     GuestPhysAddr;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(format!("{:?}", PhysAddr::from(0x1abc)), "PA:0x1abc");
+/// assert_eq!(format!("{:x}", VirtAddr::from(0x1abc)), "VA:0x1abc");
+/// assert_eq!(format!("{:X}", ExampleAddr::from(0x1abc)), "EA:0x1ABC");
+/// assert_eq!(format!("{:?}", GuestPhysAddr::from(0x1abc)), "GuestPhysAddr(0x1abc)");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! def_usize_addr_formatter {
+    (@impl $name:ident, $format:expr) => {
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_fmt(format_args!($format, format_args!("{:#x}", self.0)))
+            }
+        }
+
+        impl core::fmt::LowerHex for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_fmt(format_args!($format, format_args!("{:#x}", self.0)))
+            }
+        }
+
+        impl core::fmt::UpperHex for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_fmt(format_args!($format, format_args!("{:#X}", self.0)))
+            }
+        }
+
+        $crate::__impl_defmt_for_addr!($name, $format);
+    };
+    (
+        $name:ident = $format:literal;
+
+        $($tt:tt)*
+    ) => {
+        $crate::def_usize_addr_formatter!(@impl $name, $format);
+        $crate::def_usize_addr_formatter!($($tt)*);
+    };
+    (
+        $name:ident;
+
+        $($tt:tt)*
+    ) => {
+        $crate::def_usize_addr_formatter!(@impl $name, concat!(stringify!($name), "({})"));
+        $crate::def_usize_addr_formatter!($($tt)*);
+    };
+    () => {};
+}
+
+def_usize_addr! {
+    /// A physical memory address.
+    #[custom_debug]
+    pub type PhysAddr in crate::PhysAddrSpace;
+
+    /// A virtual memory address.
+    #[custom_debug]
+    pub type VirtAddr in crate::VirtAddrSpace;
+}
+
+def_usize_addr_formatter! {
+    PhysAddr = "PA:{}";
+    VirtAddr = "VA:{}";
+}
+
+impl VirtAddr {
+    /// The null virtual address, i.e., the address of null pointers.
+    pub const NULL: Self = Self(0);
+
+    /// The width of the virtual address space assumed by
+    /// [`is_canonical`](Self::is_canonical) and the `canonical_check` feature.
+    ///
+    /// It's 48 bits by default (e.g., x86_64 4-level paging, AArch64 48-bit
+    /// VA, and RISC-V Sv48), and can be changed by setting the
+    /// `MEMORY_ADDR_CANONICAL_BITS` environment variable when building, e.g.,
+    /// to `39` for RISC-V Sv39 or `57` for x86_64 5-level paging and RISC-V
+    /// Sv57.
+    pub const CANONICAL_BITS: u32 = match option_env!("MEMORY_ADDR_CANONICAL_BITS") {
+        Some(bits) => match u32::from_str_radix(bits, 10) {
+            Ok(bits) if bits > 0 && bits <= 64 => bits,
+            _ => panic!("invalid `MEMORY_ADDR_CANONICAL_BITS`"),
+        },
+        None => 48,
+    };
+
+    /// Checks whether the virtual address is canonical in a virtual address
+    /// space of [`CANONICAL_BITS`](Self::CANONICAL_BITS) bits, see
+    /// [`is_canonical_with_bits`](Self::is_canonical_with_bits).
+    #[inline]
+    pub const fn is_canonical(self) -> bool {
+        self.is_canonical_with_bits(Self::CANONICAL_BITS)
+    }
+
+    /// Checks whether the virtual address is canonical in a virtual address
+    /// space of `bits` bits, i.e., the bits from `bits - 1` upwards are all
+    /// equal.
+    ///
+    /// Always returns `true` if `bits` is not less than the width of `usize`,
+    /// e.g., on 32-bit targets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is zero.
+    #[inline]
+    pub const fn is_canonical_with_bits(self, bits: u32) -> bool {
+        assert!(bits > 0, "the address width must be positive");
+        if bits >= usize::BITS {
+            return true;
+        }
+        let high = (self.0 as isize) >> (bits - 1);
+        high == 0 || high == -1
+    }
+
+    /// Panics in debug builds if the `canonical_check` feature is enabled and
+    /// the address is not canonical, or not aligned for `T`.
+    ///
+    /// The alignment check catches tag bits that are still set in the low
+    /// bits of the address. It's skipped for `u8`, since any address is
+    /// aligned for it.
+    #[inline(always)]
+    const fn check_canonical<T>(self) {
+        #[cfg(feature = "canonical_check")]
+        {
+            debug_assert!(
+                self.is_canonical(),
+                "converting a non-canonical `VirtAddr` to a pointer"
+            );
+            debug_assert!(
+                self.0.is_multiple_of(core::mem::align_of::<T>()),
+                "converting a `VirtAddr` with low tag bits to a misaligned pointer"
+            );
+        }
+    }
+
+    /// Creates a new virtual address from a raw pointer.
+    #[inline]
+    pub fn from_ptr_of<T>(ptr: *const T) -> Self {
+        Self(ptr as usize)
+    }
+
+    /// Creates a new virtual address from a mutable raw pointer.
+    #[inline]
+    pub fn from_mut_ptr_of<T>(ptr: *mut T) -> Self {
+        Self(ptr as usize)
+    }
+
+    /// Converts the virtual address to a raw pointer.
+    ///
+    /// If the `canonical_check` feature is enabled, debug builds panic if the
+    /// address is not canonical (see [`VirtAddr::is_canonical`]). The same
+    /// applies to other pointer conversion methods, which also panic if the
+    /// address is not aligned for the pointee type, e.g., if tag bits in the
+    /// low bits of the address are not cleared.
+    #[inline]
+    pub const fn as_ptr(self) -> *const u8 {
+        self.check_canonical::<u8>();
+        self.0 as *const u8
+    }
+
+    /// Converts the virtual address to a raw pointer of a specific type.
+    #[inline]
+    pub const fn as_ptr_of<T>(self) -> *const T {
+        self.check_canonical::<T>();
+        self.0 as *const T
+    }
+
+    /// Converts the virtual address to a mutable raw pointer.
+    #[inline]
+    pub const fn as_mut_ptr(self) -> *mut u8 {
+        self.check_canonical::<u8>();
+        self.0 as *mut u8
+    }
+
+    /// Converts the virtual address to a mutable raw pointer of a specific
+    /// type.
+    #[inline]
+    pub const fn as_mut_ptr_of<T>(self) -> *mut T {
+        self.check_canonical::<T>();
+        self.0 as *mut T
+    }
+}
+
+/// Conversion from a raw pointer to [`VirtAddr`], same as
+/// [`VirtAddr::from_ptr_of`].
+impl<T> From<*const T> for VirtAddr {
+    #[inline]
+    fn from(ptr: *const T) -> Self {
+        Self::from_ptr_of(ptr)
+    }
+}
+
+/// Conversion from a mutable raw pointer to [`VirtAddr`], same as
+/// [`VirtAddr::from_mut_ptr_of`].
+impl<T> From<*mut T> for VirtAddr {
+    #[inline]
+    fn from(ptr: *mut T) -> Self {
+        Self::from_mut_ptr_of(ptr)
+    }
+}
+
+/// Alias for [`PhysAddr::from_usize`].
+#[macro_export]
+macro_rules! pa {
+    ($addr:expr) => {
+        $crate::PhysAddr::from_usize($addr)
+    };
+}
+
+/// Alias for [`VirtAddr::from_usize`].
+#[macro_export]
+macro_rules! va {
+    ($addr:expr) => {
+        $crate::VirtAddr::from_usize($addr)
     };
 }
 
-/// Alias for [`VirtAddr::from_usize`].
-#[macro_export]
-macro_rules! va {
-    ($addr:expr) => {
-        $crate::VirtAddr::from_usize($addr)
-    };
-}
+#[cfg(test)]
+mod test {
+    use core::mem::size_of;
+
+    use super::*;
+
+    def_usize_addr! {
+        /// An example address type.
+        #[custom_debug]
+        pub type ExampleAddr;
+        /// Another example address type.
+        #[custom_debug]
+        pub type AnotherAddr;
+    }
+
+    def_usize_addr_formatter! {
+        ExampleAddr = "EA:{}";
+        AnotherAddr = "AA:{}";
+    }
+
+    def_usize_addr! {
+        /// An example address type with the default format.
+        #[custom_debug]
+        pub type PlainAddr;
+    }
+
+    def_usize_addr_formatter! {
+        PlainAddr;
+    }
+
+    def_usize_addr! {
+        /// An example address type with signed arithmetic operators.
+        pub type SignedAddr;
+    }
+
+    def_usize_addr_signed_ops! {
+        SignedAddr;
+    }
+
+    def_usize_addr! {
+        /// An example address type comparable with `usize`.
+        pub type CmpAddr;
+    }
+
+    def_usize_addr_usize_cmp! {
+        CmpAddr;
+    }
+
+    /// An example address space.
+    pub enum ExampleSpace {}
+
+    impl crate::AddrSpace for ExampleSpace {}
+
+    def_usize_addr! {
+        /// An example address type in an example address space.
+        pub type SpacedAddr in ExampleSpace;
+        /// Another example address type in the same address space, with a
+        /// validation predicate.
+        pub type SpacedEvenAddr in ExampleSpace where is_even;
+    }
+
+    const fn is_even(addr: usize) -> bool {
+        addr & 1 == 0
+    }
+
+    def_usize_addr! {
+        /// An example address type with a validation predicate.
+        pub type EvenAddr where is_even;
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    def_usize_addr! {
+        /// An example address type with a width.
+        pub type Sv39Addr: 39 bits;
+    }
+
+    def_usize_addr! {
+        /// An example address type with a width, an address space, and a
+        /// validation predicate.
+        pub type NarrowEvenAddr: 16 bits in ExampleSpace where is_even;
+    }
+
+    def_usize_addr! {
+        /// An example address type with a custom default value.
+        pub type UnsetAddr = usize::MAX;
+
+        /// An example address type with all options.
+        pub type FullOptionAddr: 16 bits in ExampleSpace where is_even = 0xfffe;
+    }
+
+    def_usize_addr! {
+        /// An example address type relative to a base.
+        pub type RegionAddr;
+    }
+
+    def_usize_addr_base! {
+        RegionAddr = 0x8000_0000;
+    }
+
+    impl_addr_conversion!(ExampleAddr => AnotherAddr, AnotherAddr => ExampleAddr);
+
+    def_nonzero_addr! {
+        /// An example nonzero address type.
+        pub type NonZeroAddr;
+    }
+
+    def_usize_addr_formatter! {
+        NonZeroAddr = "NZ:{}";
+    }
+
+    #[test]
+    fn test_addr() {
+        let addr = va!(0x2000);
+        assert!(addr.is_aligned_4k());
+        assert!(!addr.is_aligned(0x10000usize));
+        assert_eq!(addr.align_offset_4k(), 0);
+        assert_eq!(addr.align_down_4k(), va!(0x2000));
+        assert_eq!(addr.align_up_4k(), va!(0x2000));
+
+        let addr = va!(0x2fff);
+        assert!(!addr.is_aligned_4k());
+        assert_eq!(addr.align_offset_4k(), 0xfff);
+        assert_eq!(addr.align_down_4k(), va!(0x2000));
+        assert_eq!(addr.align_up_4k(), va!(0x3000));
+
+        let align = 0x100000;
+        let addr = va!(align * 5) + 0x2000;
+        assert!(addr.is_aligned_4k());
+        assert!(!addr.is_aligned(align));
+        assert_eq!(addr.align_offset(align), 0x2000);
+        assert_eq!(addr.align_down(align), va!(align * 5));
+        assert_eq!(addr.align_up(align), va!(align * 6));
+    }
+
+    #[test]
+    pub fn test_addr_convert_and_comparison() {
+        let example1 = ExampleAddr::from_usize(0x1234);
+        let example2 = ExampleAddr::from(0x5678);
+        let another1 = AnotherAddr::from_usize(0x9abc);
+        let another2 = AnotherAddr::from(0xdef0);
+
+        assert_eq!(example1.as_usize(), 0x1234);
+        assert_eq!(Into::<usize>::into(example2), 0x5678);
+        assert_eq!(Into::<usize>::into(another1), 0x9abc);
+        assert_eq!(another2.as_usize(), 0xdef0);
+
+        assert_eq!(example1, ExampleAddr::from(0x1234));
+        assert_eq!(example2, ExampleAddr::from_usize(0x5678));
+        assert_eq!(another1, AnotherAddr::from_usize(0x9abc));
+        assert_eq!(another2, AnotherAddr::from(0xdef0));
+
+        assert!(example1 < example2);
+        assert!(example1 <= example2);
+        assert!(example2 > example1);
+        assert!(example2 >= example1);
+        assert!(example1 != example2);
+
+        const EXAMPLE1: ExampleAddr = ExampleAddr::from_usize(0x1234);
+        const EXAMPLE2: ExampleAddr = ExampleAddr::from_usize(0x5678);
+        const _: () = assert!(EXAMPLE1.lt_const(EXAMPLE2) && EXAMPLE1.le_const(EXAMPLE2));
+        const _: () = assert!(EXAMPLE2.gt_const(EXAMPLE1) && EXAMPLE2.ge_const(EXAMPLE1));
+        const _: () = assert!(EXAMPLE1.le_const(EXAMPLE1) && EXAMPLE1.ge_const(EXAMPLE1));
+        const _: () = assert!(EXAMPLE1.eq_const(EXAMPLE1) && !EXAMPLE1.eq_const(EXAMPLE2));
+        assert!(!example2.lt_const(example1));
+        assert!(!example1.gt_const(example2));
+    }
+
+    #[test]
+    pub fn test_addr_from_nonzero() {
+        use core::num::NonZeroUsize;
+
+        let nonzero = NonZeroUsize::new(0x1000).unwrap();
+        assert_eq!(PhysAddr::from(nonzero), pa!(0x1000));
+        let addr: VirtAddr = nonzero.into();
+        assert_eq!(addr, va!(0x1000));
+        // Integer literals are still inferred as `usize`.
+        assert_eq!(PhysAddr::from(0x1000), pa!(0x1000));
+    }
+
+    #[test]
+    pub fn test_addr_try_from_usize() {
+        assert_eq!(
+            EvenAddr::try_from_usize(0x1000),
+            Some(EvenAddr::from_usize(0x1000))
+        );
+        assert_eq!(EvenAddr::try_from_usize(0x1001), None);
+        assert_eq!(
+            ExampleAddr::try_from_usize(0x1001),
+            Some(ExampleAddr::from_usize(0x1001))
+        );
+
+        const VALID: Option<EvenAddr> = EvenAddr::try_from_usize(0x2000);
+        const _: () = assert!(VALID.is_some());
+    }
+
+    #[test]
+    pub fn test_addr_default() {
+        assert_eq!(ExampleAddr::default(), ExampleAddr::from_usize(0));
+        assert_eq!(ExampleAddr::DEFAULT, ExampleAddr::from_usize(0));
+
+        assert_eq!(UnsetAddr::default(), UnsetAddr::from_usize(usize::MAX));
+        assert_eq!(UnsetAddr::DEFAULT, UnsetAddr::default());
+        const UNSET: UnsetAddr = UnsetAddr::DEFAULT;
+        assert!(UNSET.eq_const(UnsetAddr::max_addr()));
+
+        assert_eq!(
+            FullOptionAddr::default(),
+            FullOptionAddr::from_usize(0xfffe)
+        );
+        assert!(FullOptionAddr::try_from_usize(FullOptionAddr::DEFAULT.as_usize()).is_some());
+    }
+
+    #[test]
+    pub fn test_addr_base() {
+        assert_eq!(RegionAddr::BASE, 0x8000_0000);
+
+        let addr = RegionAddr::from_usize(0x1234);
+        assert_eq!(addr.as_usize(), 0x1234);
+        assert_eq!(addr.to_absolute(), 0x8000_1234);
+        assert_eq!(RegionAddr::from_absolute(addr.to_absolute()), addr);
+        assert_eq!(
+            RegionAddr::from_absolute(0x8000_0000),
+            RegionAddr::from_usize(0)
+        );
+        assert_eq!(RegionAddr::checked_from_absolute(0x8000_1234), Some(addr));
+        assert_eq!(RegionAddr::checked_from_absolute(0x7fff_ffff), None);
+
+        const ABS: usize = RegionAddr::from_usize(0x1000).to_absolute();
+        assert_eq!(ABS, 0x8000_1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "address below the base in `RegionAddr::from_absolute`")]
+    pub fn test_addr_base_below() {
+        let _ = RegionAddr::from_absolute(0x7fff_ffff);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `RegionAddr::to_absolute`")]
+    pub fn test_addr_base_overflow() {
+        let _ = RegionAddr::from_usize(usize::MAX).to_absolute();
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    pub fn test_addr_width() {
+        assert_eq!(Sv39Addr::ADDR_BITS, 39);
+        assert_eq!(Sv39Addr::MASK, 0x7f_ffff_ffff);
+        assert_eq!(
+            Sv39Addr::from(0xffff_ffc0_1234_5678).truncate(),
+            Sv39Addr::from_usize(0x40_1234_5678)
+        );
+        assert_eq!(
+            Sv39Addr::try_from_usize(0x7f_ffff_ffff),
+            Some(Sv39Addr::from_usize(0x7f_ffff_ffff))
+        );
+        assert_eq!(Sv39Addr::try_from_usize(0x80_0000_0000), None);
+
+        assert_eq!(NarrowEvenAddr::MASK, 0xffff);
+        assert!(NarrowEvenAddr::try_from_usize(0xfffe).is_some());
+        assert!(NarrowEvenAddr::try_from_usize(0xffff).is_none());
+        assert!(NarrowEvenAddr::try_from_usize(0x1_0000).is_none());
+        fn is_example_space<A: crate::HasAddrSpace<Space = ExampleSpace>>(_: A) {}
+        is_example_space(NarrowEvenAddr::from_usize(0x1000));
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, target_pointer_width = "64"))]
+    #[should_panic(expected = "address exceeds the width of `Sv39Addr`")]
+    pub fn test_addr_width_exceeded() {
+        let _ = Sv39Addr::from_usize(0x80_0000_0000);
+    }
+
+    #[test]
+    pub fn test_addr_space() {
+        use crate::{HasAddrSpace, PhysAddrSpace};
+
+        fn same_space_distance<A, B>(a: A, b: B) -> usize
+        where
+            A: HasAddrSpace,
+            B: HasAddrSpace<Space = A::Space>,
+        {
+            a.into().abs_diff(b.into())
+        }
+
+        let a = SpacedAddr::from_usize(0x1000);
+        let b = SpacedEvenAddr::from_usize(0x3000);
+        assert_eq!(same_space_distance(a, b), 0x2000);
+        assert_eq!(same_space_distance(pa!(0x1000), pa!(0x1800)), 0x800);
+        assert_eq!(SpacedEvenAddr::try_from_usize(0x3001), None);
+
+        fn is_physical<A: HasAddrSpace<Space = PhysAddrSpace>>(_: A) -> bool {
+            true
+        }
+        assert!(is_physical(pa!(0x1000)));
+    }
+
+    #[test]
+    pub fn test_addr_min_max() {
+        assert_eq!(ExampleAddr::max_addr(), ExampleAddr::from_usize(usize::MAX));
+        assert_eq!(ExampleAddr::min_addr(), ExampleAddr::from_usize(0));
+        assert_eq!(<usize as MemoryAddr>::max_addr(), usize::MAX);
+        assert!(PhysAddr::min_addr() < PhysAddr::max_addr());
+    }
+
+    #[test]
+    pub fn test_addr_layout() {
+        use core::mem::{align_of, size_of};
+
+        assert_eq!(size_of::<PhysAddr>(), size_of::<usize>());
+        assert_eq!(align_of::<PhysAddr>(), align_of::<usize>());
+        assert_eq!(size_of::<VirtAddr>(), size_of::<usize>());
+        assert_eq!(align_of::<VirtAddr>(), align_of::<usize>());
+        assert_eq!(size_of::<ExampleAddr>(), size_of::<usize>());
+        assert_eq!(align_of::<ExampleAddr>(), align_of::<usize>());
+
+        #[repr(C)]
+        struct FfiRegion {
+            start: PhysAddr,
+            size: usize,
+        }
+        assert_eq!(size_of::<FfiRegion>(), size_of::<[usize; 2]>());
+        let _ = FfiRegion {
+            start: pa!(0x1000),
+            size: 0x1000,
+        };
+    }
+
+    #[test]
+    pub fn test_addr_bit_ops() {
+        let addr = va!(0x1234_5678);
+
+        assert_eq!(addr & !0xfff, addr.align_down_4k());
+        assert_eq!(addr & 0xfff, va!(0x678));
+        assert_eq!(addr | 0xfff, va!(0x1234_5fff));
+        assert_eq!(addr ^ 0xff, va!(0x1234_5687));
+        assert_eq!(!addr, va!(!0x1234_5678));
+        assert_eq!(!!addr, addr);
+
+        let mut addr = addr;
+        addr &= !0xfff;
+        assert_eq!(addr, va!(0x1234_5000));
+        addr |= 0x1;
+        assert_eq!(addr, va!(0x1234_5001));
+        addr ^= 0x1001;
+        assert_eq!(addr, va!(0x1234_4000));
+    }
+
+    #[test]
+    pub fn test_addr_bytes() {
+        let addr = pa!(0x1234_5678);
+
+        let le = addr.to_le_bytes();
+        let be = addr.to_be_bytes();
+        assert_eq!(le, 0x1234_5678usize.to_le_bytes());
+        assert_eq!(be, 0x1234_5678usize.to_be_bytes());
+        assert_eq!(le[..4], [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(be[be.len() - 4..], [0x12, 0x34, 0x56, 0x78]);
+
+        assert_eq!(PhysAddr::from_le_bytes(le), addr);
+        assert_eq!(PhysAddr::from_be_bytes(be), addr);
+        assert_ne!(PhysAddr::from_le_bytes(be), addr);
+
+        const ADDR: VirtAddr = VirtAddr::from_be_bytes(VirtAddr::from_usize(0x1000).to_be_bytes());
+        assert_eq!(ADDR, va!(0x1000));
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    pub fn test_addr_derive() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, crate::MemoryAddr)]
+        #[repr(transparent)]
+        struct DerivedAddr(usize);
+
+        const ADDR: DerivedAddr = DerivedAddr::from_usize(0x1234);
+        assert_eq!(ADDR.as_usize(), 0x1234);
+        assert_eq!(DerivedAddr::from(0x1234), ADDR);
+        assert_eq!(usize::from(ADDR), 0x1234);
+        assert_eq!(ADDR + 0x10, DerivedAddr(0x1244));
+        assert_eq!(ADDR - 0x34, DerivedAddr(0x1200));
+        assert_eq!(ADDR - DerivedAddr(0x1000), 0x234);
+
+        let mut addr = ADDR;
+        addr += 0x1000;
+        addr -= 0x234;
+        assert_eq!(addr, DerivedAddr(0x2000));
+
+        // `MemoryAddr` is implemented as well.
+        assert_eq!(ADDR.align_down_4k(), DerivedAddr(0x1000));
+        assert_eq!(ADDR.align_up(0x100usize), DerivedAddr(0x1300));
+    }
+
+    #[test]
+    pub fn test_addr_usize_ref() {
+        let mut addr = ExampleAddr::from_usize(0x1000);
+        assert_eq!(*addr.as_usize_ref(), 0x1000);
+
+        *addr.as_usize_mut() |= 0x7;
+        assert_eq!(addr, ExampleAddr::from_usize(0x1007));
+        *addr.as_usize_mut() += 0x1000;
+        assert_eq!(addr.as_usize(), 0x2007);
+    }
+
+    #[test]
+    pub fn test_addr_slice_cast() {
+        let addrs = [pa!(0x1000), pa!(0x2000), pa!(0x3000)];
+        let raw = PhysAddr::slice_as_usize(&addrs);
+        assert_eq!(raw, &[0x1000, 0x2000, 0x3000]);
+        assert_eq!(raw.as_ptr() as usize, addrs.as_ptr() as usize);
+        assert_eq!(PhysAddr::slice_from_usize(raw), &addrs);
+        assert!(ExampleAddr::slice_from_usize(&[]).is_empty());
+    }
+
+    #[test]
+    pub fn test_addr_bits() {
+        let addr = ExampleAddr::from_usize(0x1234_5000);
+        let mask = 0xfffusize;
+
+        let tagged = addr.set_bits(mask, 0x5a5);
+        assert_eq!(tagged, ExampleAddr::from_usize(0x1234_55a5));
+        assert_eq!(tagged.clear_bits(mask), addr);
+
+        // Bits of `value` outside `mask` are ignored.
+        assert_eq!(
+            addr.set_bits(mask, 0xf_f123),
+            ExampleAddr::from_usize(0x1234_5123)
+        );
+        assert_eq!(
+            addr.set_bits(0xf000, 0),
+            ExampleAddr::from_usize(0x1234_0000)
+        );
+    }
+
+    #[test]
+    pub fn test_nonzero_addr() {
+        use core::num::NonZeroUsize;
+
+        assert_eq!(size_of::<Option<NonZeroAddr>>(), size_of::<usize>());
+
+        let addr = NonZeroAddr::new(0x1000).unwrap();
+        assert_eq!(addr.as_usize(), 0x1000);
+        assert_eq!(addr.as_nonzero(), NonZeroUsize::new(0x1000).unwrap());
+        assert_eq!(NonZeroAddr::from(NonZeroUsize::new(0x1000).unwrap()), addr);
+        assert_eq!(NonZeroAddr::try_from(0x1000), Ok(addr));
+        assert_eq!(usize::from(addr), 0x1000);
+        assert_eq!(format!("{:?}", addr), "NZ:0x1000");
+
+        assert_eq!(NonZeroAddr::new(0), None);
+        assert!(NonZeroAddr::try_from(0).is_err());
+        assert!(addr < NonZeroAddr::new(0x2000).unwrap());
+    }
+
+    #[test]
+    pub fn test_addr_conversion() {
+        let example = ExampleAddr::from_usize(0x1234);
+        let another: AnotherAddr = example.into();
+        assert_eq!(another.as_usize(), 0x1234);
+        assert_eq!(ExampleAddr::from(another), example);
+    }
+
+    #[test]
+    pub fn test_addr_fmt() {
+        assert_eq!(format!("{:?}", ExampleAddr::from(0x1abc)), "EA:0x1abc");
+        assert_eq!(format!("{:x}", AnotherAddr::from(0x1abc)), "AA:0x1abc");
+        assert_eq!(format!("{:X}", ExampleAddr::from(0x1abc)), "EA:0x1ABC");
+
+        assert_eq!(
+            format!("{:?}", PlainAddr::from(0x1abc)),
+            "PlainAddr(0x1abc)"
+        );
+        assert_eq!(
+            format!("{:x}", PlainAddr::from(0x1abc)),
+            "PlainAddr(0x1abc)"
+        );
+        assert_eq!(
+            format!("{:X}", PlainAddr::from(0x1abc)),
+            "PlainAddr(0x1ABC)"
+        );
+
+        // Without a formatter, `Debug` is implemented by `def_usize_addr`.
+        assert_eq!(
+            format!("{:?}", SignedAddr::from(0x1abc)),
+            "SignedAddr(0x1abc)"
+        );
+        assert_eq!(format!("{:?}", EvenAddr::from(0)), "EvenAddr(0x0)");
+    }
+
+    #[test]
+    pub fn test_alignment() {
+        let alignment = 0x1000usize;
+        let base = alignment * 2;
+        let offset = 0x123usize;
+        let addr = ExampleAddr::from_usize(base + offset);
+
+        assert_eq!(addr.align_down(alignment), ExampleAddr::from_usize(base));
+        assert_eq!(
+            addr.align_up(alignment),
+            ExampleAddr::from_usize(base + alignment)
+        );
+        assert_eq!(addr.align_offset(alignment), offset);
+        assert!(!addr.is_aligned(alignment));
+        assert!(ExampleAddr::from_usize(base).is_aligned(alignment));
+        assert_eq!(
+            ExampleAddr::from_usize(base).align_up(alignment),
+            ExampleAddr::from_usize(base)
+        );
+    }
+
+    #[test]
+    pub fn test_align_up_offset() {
+        let alignment = 0x1000usize;
+
+        assert_eq!(
+            ExampleAddr::from_usize(0x3000).align_up_offset(alignment),
+            0
+        );
+        assert_eq!(
+            ExampleAddr::from_usize(0x3001).align_up_offset(alignment),
+            0xfff
+        );
+        assert_eq!(
+            ExampleAddr::from_usize(0x3123).align_up_offset(alignment),
+            0xedd
+        );
+        assert_eq!(
+            ExampleAddr::from_usize(usize::MAX).align_up_offset(alignment),
+            1
+        );
+
+        let addr = ExampleAddr::from_usize(0x12345);
+        assert_eq!(
+            addr + addr.align_up_offset(alignment),
+            addr.align_up(alignment)
+        );
+    }
+
+    #[test]
+    pub fn test_distance_to_alignment_of() {
+        let align = 0x20_0000usize;
+        let target = ExampleAddr::from_usize(0x4567_8000);
+        let target_offset = target.align_offset(align);
+
+        for addr in [
+            0,
+            0x1000,
+            0x1234_5000,
+            0x4567_8000,
+            0x4577_8000,
+            usize::MAX - 0xfff,
+        ] {
+            let addr = ExampleAddr::from_usize(addr);
+            let distance = addr.distance_to_alignment_of(target, align);
+            assert!(distance < align);
+            assert_eq!(
+                addr.wrapping_add(distance).align_offset(align),
+                target_offset
+            );
+        }
+
+        let addr = ExampleAddr::from_usize(0x1234_5000);
+        assert_eq!(addr.distance_to_alignment_of(target, align), 0x13_3000);
+        assert_eq!(target.distance_to_alignment_of(addr, align), 0xc_d000);
+        assert_eq!(target.distance_to_alignment_of(target, align), 0);
+        assert_eq!(
+            addr.distance_to_alignment_of(ExampleAddr::from_usize(0x4000_0000), align),
+            addr.align_up_offset(align)
+        );
+    }
+
+    #[test]
+    pub fn test_next_prev_aligned() {
+        let alignment = 0x1000usize;
+        let aligned = ExampleAddr::from_usize(0x3000);
+        let unaligned = ExampleAddr::from_usize(0x3123);
+
+        assert_eq!(
+            aligned.next_aligned(alignment),
+            ExampleAddr::from_usize(0x4000)
+        );
+        assert_eq!(
+            unaligned.next_aligned(alignment),
+            ExampleAddr::from_usize(0x4000)
+        );
+        assert_eq!(
+            aligned.prev_aligned(alignment),
+            ExampleAddr::from_usize(0x2000)
+        );
+        assert_eq!(
+            unaligned.prev_aligned(alignment),
+            ExampleAddr::from_usize(0x3000)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_next_aligned_overflow() {
+        let addr = ExampleAddr::from_usize(usize::MAX);
+        let _ = addr.next_aligned(0x1000usize);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_prev_aligned_underflow() {
+        let addr = ExampleAddr::from_usize(0);
+        let _ = addr.prev_aligned(0x1000usize);
+    }
+
+    #[test]
+    pub fn test_align_changed() {
+        let alignment = 0x1000usize;
+        let aligned = ExampleAddr::from_usize(0x3000);
+        let unaligned = ExampleAddr::from_usize(0x3123);
+
+        assert_eq!(aligned.align_down_changed(alignment), (aligned, false));
+        assert_eq!(aligned.align_up_changed(alignment), (aligned, false));
+        assert_eq!(
+            unaligned.align_down_changed(alignment),
+            (ExampleAddr::from_usize(0x3000), true)
+        );
+        assert_eq!(
+            unaligned.align_up_changed(alignment),
+            (ExampleAddr::from_usize(0x4000), true)
+        );
+    }
+
+    #[test]
+    pub fn test_align_down_with_mask() {
+        let addr = ExampleAddr::from_usize(0x1234_5678);
+        for shift in 0..usize::BITS {
+            let align = 1usize << shift;
+            let (aligned, mask) = addr.align_down_with_mask(align);
+            assert_eq!(mask, align - 1);
+            assert_eq!(aligned, addr.align_down(align));
+            assert_eq!(aligned.as_usize(), addr.as_usize() & !mask);
+        }
+        assert_eq!(
+            addr.align_down_with_mask(0x1000usize),
+            (ExampleAddr::from_usize(0x1234_5000), 0xfff)
+        );
+    }
+
+    #[test]
+    pub fn test_split_align() {
+        let addr = ExampleAddr::from_usize(0x12345678);
+
+        let (base, offset) = addr.split_4k();
+        assert_eq!(base, ExampleAddr::from_usize(0x12345000));
+        assert_eq!(offset, 0x678);
+        assert_eq!(base + offset, addr);
+
+        let (base, offset) = addr.split_align(0x100000usize);
+        assert_eq!(base, ExampleAddr::from_usize(0x12300000));
+        assert_eq!(offset, 0x45678);
+        assert_eq!(base + offset, addr);
+
+        assert_eq!(base.split_4k(), (base, 0));
+    }
+
+    #[test]
+    pub fn test_with_page_offset() {
+        let addr = ExampleAddr::from_usize(0x1234_5678);
+
+        assert_eq!(
+            addr.with_page_offset(0x9ab, 0x1000),
+            ExampleAddr::from_usize(0x1234_59ab)
+        );
+        assert_eq!(addr.with_page_offset(0, 0x1000), addr.align_down_4k());
+        assert_eq!(
+            addr.with_page_offset(0x1f_ffff, 0x20_0000),
+            ExampleAddr::from_usize(0x123f_ffff)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "offset out of page in `MemoryAddr::with_page_offset`")]
+    pub fn test_with_page_offset_out_of_page() {
+        let _ = ExampleAddr::from_usize(0x1234_5678).with_page_offset(0x1000, 0x1000);
+    }
+
+    #[test]
+    pub fn test_crosses_boundary() {
+        // within a page
+        assert!(!va!(0x1000).crosses_page_4k(0x1000));
+        assert!(!va!(0x1800).crosses_page_4k(0x800));
+        assert!(!va!(0x1fff).crosses_page_4k(1));
+        assert!(!va!(0x1234).crosses_page_4k(0));
+        // straddling a page boundary
+        assert!(va!(0x1000).crosses_page_4k(0x1001));
+        assert!(va!(0x1800).crosses_page_4k(0x801));
+        assert!(va!(0x1fff).crosses_page_4k(2));
+
+        assert!(!va!(0x1_0000).crosses_boundary(0x1_0000, 0x1_0000usize));
+        assert!(va!(0x1_fff0).crosses_boundary(0x20, 0x1_0000usize));
+        assert!(!va!(0x1_fff0).crosses_boundary(0x20, 0x20_0000usize));
+
+        // at the top of the address space
+        assert!(!va!(usize::MAX - 0xfff).crosses_page_4k(0x1000));
+        assert!(va!(usize::MAX - 0xfff).crosses_page_4k(0x1001));
+        assert!(va!(usize::MAX).crosses_page_4k(usize::MAX));
+    }
+
+    #[test]
+    pub fn test_can_use_superpage() {
+        // aligned and large enough
+        assert!(pa!(0x20_0000).can_use_superpage_2m(0x20_0000));
+        assert!(pa!(0x4000_0000).can_use_superpage_2m(0x1234_5678));
+        assert!(pa!(0x4000_0000).can_use_superpage_1g(0x4000_0000));
+        // aligned but too small
+        assert!(!pa!(0x20_0000).can_use_superpage_2m(0x1f_f000));
+        assert!(!pa!(0x4000_0000).can_use_superpage_1g(0x3fe0_0000));
+        assert!(!pa!(0).can_use_superpage_2m(0));
+        // unaligned
+        assert!(!pa!(0x20_1000).can_use_superpage_2m(0x40_0000));
+        assert!(!pa!(0x20_0000).can_use_superpage_1g(0x8000_0000));
+
+        assert!(va!(0x1_0000).can_use_superpage(0x1_0000, 0x1_0000usize));
+        assert!(!va!(0x1_0000).can_use_superpage(0x1_0000, 0x2_0000usize));
+    }
+
+    #[test]
+    pub fn test_largest_alignment() {
+        const PAGE_SIZES: [usize; 3] = [0x1000, 0x20_0000, 0x4000_0000];
+
+        assert_eq!(
+            pa!(0x1234_5000).largest_alignment(&PAGE_SIZES),
+            Some(0x1000)
+        );
+        assert_eq!(
+            pa!(0x1220_0000).largest_alignment(&PAGE_SIZES),
+            Some(0x20_0000)
+        );
+        assert_eq!(
+            pa!(0x8000_0000).largest_alignment(&PAGE_SIZES),
+            Some(0x4000_0000)
+        );
+        assert_eq!(pa!(0).largest_alignment(&PAGE_SIZES), Some(0x4000_0000));
+        assert_eq!(pa!(0x1234_5678).largest_alignment(&PAGE_SIZES), None);
+        assert_eq!(pa!(0x1234_5678).largest_alignment(&[]), None);
+        // The order of the candidates doesn't matter.
+        assert_eq!(
+            pa!(0x1220_0000).largest_alignment(&[0x20_0000, 0x1000, 0x4000_0000]),
+            Some(0x20_0000)
+        );
+    }
+
+    #[test]
+    pub fn test_checked_align_up_to_order() {
+        let addr = ExampleAddr::from_usize(0x12345678);
+        assert_eq!(addr.checked_align_up_to_order(0), Some(addr.align_up_4k()));
+        assert_eq!(
+            addr.checked_align_up_to_order(9),
+            Some(addr.align_up_order(9))
+        );
+
+        let high = ExampleAddr::from_usize(usize::MAX - 0x10_0000);
+        assert_eq!(high.checked_align_up_to_order(9), None);
+        assert_eq!(
+            high.checked_align_up_to_order(0),
+            Some(ExampleAddr::from_usize(usize::MAX - 0xf_ffff))
+        );
+        assert_eq!(addr.checked_align_up_to_order(usize::BITS - 12), None);
+        assert_eq!(addr.checked_align_up_to_order(u32::MAX), None);
+        assert_eq!(
+            ExampleAddr::from_usize(0).checked_align_up_to_order(usize::BITS - 13),
+            Some(ExampleAddr::from_usize(0))
+        );
+    }
+
+    #[test]
+    pub fn test_align_shift() {
+        let addr = ExampleAddr::from_usize(0x12345678);
+
+        assert_eq!(addr.align_down_shift(12), addr.align_down_4k());
+        assert_eq!(addr.align_up_shift(12), addr.align_up_4k());
+        assert_eq!(addr.is_aligned_shift(12), addr.is_aligned_4k());
+        assert!(addr.align_down_4k().is_aligned_shift(12));
+        assert_eq!(addr.align_down_shift(0), addr);
+        assert_eq!(addr.align_up_shift(0), addr);
+        assert!(addr.is_aligned_shift(3));
+        assert!(!addr.is_aligned_shift(4));
+        assert_eq!(
+            addr.align_down_shift(usize::BITS - 1),
+            ExampleAddr::from_usize(0)
+        );
+    }
+
+    #[test]
+    pub fn test_addr_zeros() {
+        let addr = pa!(0x1234_5000);
+        assert_eq!(addr.trailing_zeros(), 12);
+        assert!(addr.is_aligned_shift(addr.trailing_zeros()));
+        assert!(!addr.is_aligned(crate::PAGE_SIZE_2M));
+        assert_eq!(pa!(0x20_0000).trailing_zeros(), 21);
+        assert_eq!(pa!(0x1).trailing_zeros(), 0);
+        assert_eq!(pa!(0).trailing_zeros(), usize::BITS);
+
+        assert_eq!(pa!(usize::MAX).leading_zeros(), 0);
+        assert_eq!(pa!(0x1000).leading_zeros(), usize::BITS - 13);
+        assert_eq!(pa!(0).leading_zeros(), usize::BITS);
+    }
+
+    #[test]
+    pub fn test_addr_byte_order() {
+        let addr = pa!(0x1234_5678);
+
+        assert_eq!(addr.swap_bytes(), pa!(0x1234_5678usize.swap_bytes()));
+        assert_eq!(addr.swap_bytes().swap_bytes(), addr);
+        assert_eq!(addr.to_le().to_le(), addr);
+        assert_eq!(addr.to_be().to_be(), addr);
+        // The native representations after conversion are in the specified endianness.
+        assert_eq!(addr.to_le().as_usize().to_ne_bytes(), addr.to_le_bytes());
+        assert_eq!(addr.to_be().as_usize().to_ne_bytes(), addr.to_be_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "shift too large in `MemoryAddr::align_down_shift`")]
+    pub fn test_align_shift_too_large() {
+        let _ = ExampleAddr::from_usize(0x1000).align_down_shift(usize::BITS);
+    }
+
+    #[test]
+    pub fn test_align_u64() {
+        let addr = pa!(0x1234_5678);
+
+        assert_eq!(addr.align_down_u64(0x1000), addr.align_down_4k());
+        assert_eq!(addr.align_up_u64(0x1000), addr.align_up_4k());
+        assert_eq!(addr.align_down_u64(0x20_0000), pa!(0x1220_0000));
+        assert_eq!(addr.align_up_u64(1), addr);
+
+        // On 32-bit targets, these alignments don't fit in `usize`.
+        assert_eq!(addr.align_down_u64(1 << 40), pa!(0));
+        assert_eq!(pa!(0).align_up_u64(1 << 40), pa!(0));
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(addr.align_up_u64(1 << 40), pa!(1 << 40));
+    }
+
+    #[test]
+    pub fn test_align_order() {
+        let addr = ExampleAddr::from_usize(0x12345678);
+
+        assert_eq!(addr.align_up_order(0), addr.align_up_4k());
+        assert_eq!(addr.align_down_order(0), addr.align_down_4k());
+        assert_eq!(addr.align_up_order(1), ExampleAddr::from_usize(0x12346000));
+        assert_eq!(
+            addr.align_down_order(1),
+            ExampleAddr::from_usize(0x12344000)
+        );
+        assert_eq!(addr.align_up_order(9), ExampleAddr::from_usize(0x12400000));
+        assert_eq!(
+            addr.align_down_order(9),
+            ExampleAddr::from_usize(0x12200000)
+        );
+        assert!(addr
+            .align_up_order(9)
+            .is_aligned(crate::page_size_of_order(9)));
+    }
+
+    #[test]
+    pub fn test_page_number_4k() {
+        let addr = ExampleAddr::from_usize(0x12345000);
+        assert_eq!(addr.page_number_4k(), 0x12345);
+        assert_eq!(
+            ExampleAddr::from_page_number_4k(addr.page_number_4k()),
+            addr
+        );
+        assert_eq!(
+            ExampleAddr::from_usize(0x12345fff).page_number_4k(),
+            0x12345
+        );
+        assert_eq!(PhysAddr::from_page_number_4k(0), pa!(0));
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_from_page_number_4k_overflow() {
+        let _ = ExampleAddr::from_page_number_4k(usize::MAX);
+    }
+
+    #[test]
+    pub fn test_align_by_addr() {
+        let addr = pa!(0x1234_5678);
+        let superpage = pa!(0x20_0000);
+
+        assert_eq!(addr.align_down(superpage), pa!(0x1220_0000));
+        assert_eq!(addr.align_up(superpage), pa!(0x1240_0000));
+        assert_eq!(addr.align_offset(superpage), 0x14_5678);
+        assert!(!addr.is_aligned(superpage));
+        assert!(superpage.is_aligned(superpage));
+
+        // The alignment can even be of another address type.
+        let va = va!(0x1234_5678);
+        assert_eq!(va.align_down(superpage), va!(0x1220_0000));
+        assert_eq!(va.align_up(superpage), va!(0x1240_0000));
+    }
+
+    #[test]
+    pub fn test_align_const() {
+        for addr in [0, 1, 0xfff, 0x1000, 0x1234_5678, usize::MAX - 0x1fff] {
+            let addr = ExampleAddr::from_usize(addr);
+            assert_eq!(addr.align_down_const::<0x1000>(), addr.align_down_4k());
+            assert_eq!(addr.align_up_const::<0x1000>(), addr.align_up_4k());
+            assert_eq!(
+                addr.align_down_const::<0x20_0000>(),
+                addr.align_down(0x20_0000usize)
+            );
+            assert_eq!(addr.align_up_const::<1>(), addr);
+        }
+        assert_eq!(
+            ExampleAddr::from_usize(usize::MAX).align_down_const::<0x1000>(),
+            ExampleAddr::from_usize(usize::MAX - 0xfff)
+        );
+    }
+
+    #[test]
+    pub fn test_checked_align() {
+        let addr = ExampleAddr::from_usize(0x1234);
+
+        assert_eq!(addr.checked_align_down(0usize), None);
+        assert_eq!(addr.checked_align_up(0usize), None);
+        assert_eq!(addr.checked_align_down(0x1001usize), None);
+        assert_eq!(addr.checked_align_up(0x1001usize), None);
+        assert_eq!(
+            addr.checked_align_down(0x1000usize),
+            Some(ExampleAddr::from_usize(0x1000))
+        );
+        assert_eq!(
+            addr.checked_align_up(0x1000usize),
+            Some(ExampleAddr::from_usize(0x2000))
+        );
+        assert_eq!(addr.checked_align_up(1usize), Some(addr));
+
+        let max = ExampleAddr::from_usize(usize::MAX);
+        assert_eq!(max.checked_align_up(0x1000usize), None);
+        assert_eq!(max.checked_align_up(1usize), Some(max));
+    }
+
+    #[test]
+    pub fn test_try_align_up() {
+        let addr = ExampleAddr::from_usize(0x1234);
+
+        assert_eq!(
+            addr.try_align_up(0x1000usize),
+            Ok(ExampleAddr::from_usize(0x2000))
+        );
+        assert_eq!(addr.try_align_up(1usize), Ok(addr));
+        assert_eq!(addr.try_align_up(0usize), Err(AlignError::NotPowerOfTwo));
+        assert_eq!(
+            addr.try_align_up(0x1001usize),
+            Err(AlignError::NotPowerOfTwo)
+        );
+
+        let max = ExampleAddr::from_usize(usize::MAX);
+        assert_eq!(max.try_align_up(0x1000usize), Err(AlignError::Overflow));
+        // An invalid alignment is reported before the overflow.
+        assert_eq!(
+            max.try_align_up(0x1001usize),
+            Err(AlignError::NotPowerOfTwo)
+        );
+
+        assert_eq!(
+            AlignError::NotPowerOfTwo.to_string(),
+            "alignment is not a power of two"
+        );
+        assert_eq!(
+            AlignError::Overflow.to_string(),
+            "aligned address overflows"
+        );
+    }
+
+    #[test]
+    pub fn test_checked_page_4k() {
+        let addr = ExampleAddr::from_usize(0x1234);
+        let aligned = ExampleAddr::from_usize(0x2000);
+
+        assert_eq!(addr.checked_align_up_4k(), Some(aligned));
+        assert_eq!(aligned.checked_align_up_4k(), Some(aligned));
+        assert_eq!(addr.checked_next_page_4k(), Some(aligned));
+        assert_eq!(
+            aligned.checked_next_page_4k(),
+            Some(ExampleAddr::from_usize(0x3000))
+        );
+
+        let top = ExampleAddr::from_usize(usize::MAX - 1);
+        assert_eq!(top.checked_align_up_4k(), None);
+        assert_eq!(top.checked_next_page_4k(), None);
+        let last_page = ExampleAddr::from_usize(usize::MAX & !0xfff);
+        assert_eq!(last_page.checked_align_up_4k(), Some(last_page));
+        assert_eq!(last_page.checked_next_page_4k(), None);
+    }
+
+    #[test]
+    pub fn test_page_4k_navigation() {
+        // unaligned starting points
+        let addr = ExampleAddr::from_usize(0x1234);
+        assert_eq!(addr.next_page_4k(), ExampleAddr::from_usize(0x2000));
+        assert_eq!(addr.prev_page_4k(), ExampleAddr::from_usize(0x1000));
+        assert_eq!(addr.checked_prev_page_4k(), Some(addr.prev_page_4k()));
+        // aligned starting points
+        let addr = ExampleAddr::from_usize(0x2000);
+        assert_eq!(addr.next_page_4k(), ExampleAddr::from_usize(0x3000));
+        assert_eq!(addr.prev_page_4k(), ExampleAddr::from_usize(0x1000));
+        assert_eq!(addr.next_page_4k().prev_page_4k(), addr);
+        assert_eq!(addr.checked_prev_page_4k(), Some(addr.prev_page_4k()));
+
+        for addr in [0x1, 0xfff, 0x1000, 0x1001, 0x1234_5678] {
+            let addr = ExampleAddr::from_usize(addr);
+            assert_eq!(addr.next_page_4k(), addr.next_aligned(0x1000usize));
+            assert_eq!(addr.prev_page_4k(), addr.prev_aligned(0x1000usize));
+        }
+
+        // at the boundaries
+        let zero = ExampleAddr::from_usize(0);
+        assert_eq!(zero.checked_prev_page_4k(), None);
+        assert_eq!(
+            ExampleAddr::from_usize(0xfff).checked_prev_page_4k(),
+            Some(zero)
+        );
+        assert_eq!(zero.next_page_4k(), ExampleAddr::from_usize(0x1000));
+        let max = ExampleAddr::from_usize(usize::MAX);
+        assert_eq!(
+            max.prev_page_4k(),
+            ExampleAddr::from_usize(usize::MAX & !0xfff)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::next_page_4k`")]
+    pub fn test_next_page_4k_overflow() {
+        let _ = ExampleAddr::from_usize(usize::MAX - 1).next_page_4k();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::prev_page_4k`: 0x0 aligned to 0x1000")]
+    pub fn test_prev_page_4k_underflow() {
+        let _ = ExampleAddr::from_usize(0).prev_page_4k();
+    }
+
+    #[test]
+    pub fn test_wrapping_align() {
+        let addr = ExampleAddr::from_usize(0x1234);
+        assert_eq!(addr.wrapping_align_up(0x1000usize), addr.align_up_4k());
+        assert_eq!(addr.wrapping_align_down(0x1000usize), addr.align_down_4k());
+
+        let max = ExampleAddr::from_usize(usize::MAX);
+        assert_eq!(
+            max.wrapping_align_up(0x1000usize),
+            ExampleAddr::from_usize(0)
+        );
+        assert_eq!(max.wrapping_align_up(1usize), max);
+        assert_eq!(
+            max.wrapping_align_down(0x1000usize),
+            ExampleAddr::from_usize(usize::MAX & !0xfff)
+        );
+        let last_page = ExampleAddr::from_usize(usize::MAX & !0xfff);
+        assert_eq!(last_page.wrapping_align_up(0x1000usize), last_page);
+    }
 
-#[cfg(test)]
-mod test {
-    use core::mem::size_of;
+    #[test]
+    pub fn test_checked_align_offset() {
+        let addr = ExampleAddr::from_usize(0x1234);
 
-    use super::*;
+        assert_eq!(addr.checked_align_offset(0usize), None);
+        assert_eq!(addr.checked_align_offset(3usize), None);
+        assert_eq!(addr.checked_align_offset(0x1001usize), None);
+        assert_eq!(addr.checked_align_offset(1usize), Some(0));
+        assert_eq!(addr.checked_align_offset(0x1000usize), Some(0x234));
+    }
 
-    def_usize_addr! {
-        /// An example address type.
-        pub type ExampleAddr;
-        /// Another example address type.
-        pub type AnotherAddr;
+    #[test]
+    pub fn test_align_unchecked() {
+        let alignment = 0x1000usize;
+        for addr in [0x0, 0x1, 0x2fff, 0x3000, 0x3123, usize::MAX - 0x1fff] {
+            let addr = ExampleAddr::from_usize(addr);
+            unsafe {
+                assert_eq!(
+                    addr.align_down_unchecked(alignment),
+                    addr.align_down(alignment)
+                );
+                assert_eq!(addr.align_up_unchecked(alignment), addr.align_up(alignment));
+            }
+        }
     }
 
-    def_usize_addr_formatter! {
-        ExampleAddr = "EA:{}";
-        AnotherAddr = "AA:{}";
+    #[test]
+    pub fn test_align_for_layout() {
+        let layout = Layout::from_size_align(0x100, 0x40).unwrap();
+        let addr = ExampleAddr::from_usize(0x1010);
+        let end = ExampleAddr::from_usize(0x1140);
+
+        assert_eq!(
+            addr.align_down_for_layout(layout),
+            ExampleAddr::from_usize(0x1000)
+        );
+        assert_eq!(
+            addr.align_up_for_layout(layout),
+            ExampleAddr::from_usize(0x1040)
+        );
+        assert!(addr.fits_layout(layout, end));
+        assert!(!addr.fits_layout(layout, end.sub(1)));
+        assert!(!ExampleAddr::from_usize(usize::MAX - 0x80)
+            .fits_layout(layout, ExampleAddr::max_addr()));
+        assert!(!ExampleAddr::from_usize(usize::MAX).fits_layout(layout, ExampleAddr::max_addr()));
     }
 
     #[test]
-    fn test_addr() {
-        let addr = va!(0x2000);
-        assert!(addr.is_aligned_4k());
-        assert!(!addr.is_aligned(0x10000usize));
-        assert_eq!(addr.align_offset_4k(), 0);
-        assert_eq!(addr.align_down_4k(), va!(0x2000));
-        assert_eq!(addr.align_up_4k(), va!(0x2000));
+    pub fn test_addr_arithmetic() {
+        let base = 0x1234usize;
+        let offset = 0x100usize;
+        let with_offset = base + offset;
 
-        let addr = va!(0x2fff);
-        assert!(!addr.is_aligned_4k());
-        assert_eq!(addr.align_offset_4k(), 0xfff);
-        assert_eq!(addr.align_down_4k(), va!(0x2000));
-        assert_eq!(addr.align_up_4k(), va!(0x3000));
+        let addr = ExampleAddr::from_usize(base);
+        let offset_addr = ExampleAddr::from_usize(with_offset);
 
-        let align = 0x100000;
-        let addr = va!(align * 5) + 0x2000;
-        assert!(addr.is_aligned_4k());
-        assert!(!addr.is_aligned(align));
-        assert_eq!(addr.align_offset(align), 0x2000);
-        assert_eq!(addr.align_down(align), va!(align * 5));
-        assert_eq!(addr.align_up(align), va!(align * 6));
+        assert_eq!(addr.offset(offset as isize), offset_addr);
+        assert_eq!(addr.wrapping_offset(offset as isize), offset_addr);
+        assert_eq!(offset_addr.offset_from(addr), offset as isize);
+        assert_eq!(offset_addr.wrapping_offset_from(addr), offset as isize);
+        assert_eq!(addr.add(offset), offset_addr);
+        assert_eq!(addr.wrapping_add(offset), offset_addr);
+        assert_eq!(offset_addr.sub(offset), addr);
+        assert_eq!(offset_addr.wrapping_sub(offset), addr);
+        assert_eq!(offset_addr.sub_addr(addr), offset);
+        assert_eq!(offset_addr.wrapping_sub_addr(addr), offset);
+
+        assert_eq!(addr + offset, offset_addr);
+        assert_eq!(offset_addr - offset, addr);
+        assert_eq!(offset_addr - addr, offset);
     }
 
     #[test]
-    pub fn test_addr_convert_and_comparison() {
-        let example1 = ExampleAddr::from_usize(0x1234);
-        let example2 = ExampleAddr::from(0x5678);
-        let another1 = AnotherAddr::from_usize(0x9abc);
-        let another2 = AnotherAddr::from(0xdef0);
+    pub fn test_addr_signed_ops() {
+        let base = 0x1234usize;
+        let offset = 0x100isize;
 
-        assert_eq!(example1.as_usize(), 0x1234);
-        assert_eq!(Into::<usize>::into(example2), 0x5678);
-        assert_eq!(Into::<usize>::into(another1), 0x9abc);
-        assert_eq!(another2.as_usize(), 0xdef0);
+        let addr = SignedAddr::from_usize(base);
+        let offset_addr = SignedAddr::from_usize(base + offset as usize);
+
+        assert_eq!(addr + offset, offset_addr);
+        assert_eq!(addr + offset, addr.offset(offset));
+        assert_eq!(offset_addr + (-offset), addr);
+        assert_eq!(offset_addr - offset, addr);
+        assert_eq!(addr - (-offset), offset_addr);
+        assert_eq!(addr + 0x100usize, offset_addr);
+
+        let mut addr_mut = addr;
+        addr_mut += offset;
+        assert_eq!(addr_mut, offset_addr);
+        addr_mut -= offset;
+        assert_eq!(addr_mut, addr);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_addr_signed_ops_overflow() {
+        let _ = SignedAddr::from_usize(usize::MAX) + 1isize;
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_addr_signed_ops_underflow() {
+        let _ = SignedAddr::from_usize(0) - 1isize;
+    }
+
+    #[test]
+    pub fn test_addr_wrapping_arithmetic() {
+        let base = usize::MAX - 0x100usize;
+        let offset = 0x200usize;
+        let with_offset = base.wrapping_add(offset);
+
+        let addr = ExampleAddr::from_usize(base);
+        let offset_addr = ExampleAddr::from_usize(with_offset);
+
+        assert_eq!(addr.wrapping_offset(offset as isize), offset_addr);
+        assert_eq!(offset_addr.wrapping_offset(-(offset as isize)), addr);
+        assert_eq!(offset_addr.wrapping_offset_from(addr), offset as isize);
+        assert_eq!(addr.wrapping_offset_from(offset_addr), -(offset as isize));
+        assert_eq!(addr.wrapping_add(offset), offset_addr);
+        assert_eq!(offset_addr.wrapping_sub(offset), addr);
+        assert_eq!(offset_addr.wrapping_sub_addr(addr), offset);
+    }
+
+    #[test]
+    pub fn test_addr_checked_arithmetic() {
+        let low_addr = ExampleAddr::from_usize(0x100usize);
+        let high_addr = ExampleAddr::from_usize(usize::MAX - 0x100usize);
+        let small_offset = 0x50usize;
+        let large_offset = 0x200usize;
+
+        assert_eq!(
+            low_addr.checked_sub(small_offset),
+            Some(low_addr.wrapping_sub(small_offset))
+        );
+        assert_eq!(low_addr.checked_sub(large_offset), None);
+        assert_eq!(
+            high_addr.checked_add(small_offset),
+            Some(high_addr.wrapping_add(small_offset))
+        );
+        assert_eq!(high_addr.checked_add(large_offset), None);
+
+        assert_eq!(
+            high_addr.checked_sub_addr(low_addr),
+            Some(usize::MAX - 0x200usize)
+        );
+        assert_eq!(low_addr.checked_sub_addr(high_addr), None);
+    }
+
+    #[test]
+    pub fn test_addr_overflowing_arithmetic() {
+        let low_addr = ExampleAddr::from_usize(0x100usize);
+        let high_addr = ExampleAddr::from_usize(usize::MAX - 0x100usize);
+        let small_offset = 0x50usize;
+        let large_offset = 0x200usize;
+
+        assert_eq!(
+            low_addr.overflowing_sub(small_offset),
+            (low_addr.wrapping_sub(small_offset), false)
+        );
+        assert_eq!(
+            low_addr.overflowing_sub(large_offset),
+            (low_addr.wrapping_sub(large_offset), true)
+        );
+        assert_eq!(
+            high_addr.overflowing_add(small_offset),
+            (high_addr.wrapping_add(small_offset), false)
+        );
+        assert_eq!(
+            high_addr.overflowing_add(large_offset),
+            (high_addr.wrapping_add(large_offset), true)
+        );
+        assert_eq!(
+            high_addr.overflowing_sub_addr(low_addr),
+            (high_addr.wrapping_sub_addr(low_addr), false)
+        );
+        assert_eq!(
+            low_addr.overflowing_sub_addr(high_addr),
+            (low_addr.wrapping_sub_addr(high_addr), true)
+        );
+    }
+
+    #[test]
+    pub fn test_addr_add_reporting_overflow() {
+        let addr = ExampleAddr::from_usize(0x1000);
+        assert_eq!(
+            addr.add_reporting_overflow(0x234),
+            Ok(ExampleAddr::from_usize(0x1234))
+        );
+        assert_eq!(addr.add_reporting_overflow(0), Ok(addr));
+
+        let high = ExampleAddr::from_usize(usize::MAX - 0xff);
+        assert_eq!(
+            high.add_reporting_overflow(0xff),
+            Ok(ExampleAddr::from_usize(usize::MAX))
+        );
+        assert_eq!(high.add_reporting_overflow(0x100), Err(0));
+        assert_eq!(high.add_reporting_overflow(0x150), Err(0x50));
+        assert_eq!(
+            high.add_reporting_overflow(usize::MAX),
+            Err(high.wrapping_add(usize::MAX).as_usize())
+        );
+    }
+
+    #[test]
+    pub fn test_addr_wrapping_sub_addr() {
+        let low = ExampleAddr::from_usize(0x1000);
+        let high = ExampleAddr::from_usize(0x3800);
+        let max = ExampleAddr::from_usize(usize::MAX);
+
+        assert_eq!(high.wrapping_sub_addr(low), 0x2800);
+        assert_eq!(low.wrapping_sub_addr(low), 0);
+        // `rhs > self` wraps around.
+        assert_eq!(low.wrapping_sub_addr(high), 0x2800usize.wrapping_neg());
+        assert_eq!(low.wrapping_sub_addr(max), 0x1001);
+        assert_eq!(ExampleAddr::from_usize(0).wrapping_sub_addr(max), 1);
+        assert_eq!(high.wrapping_add(low.wrapping_sub_addr(high)), low);
+    }
+
+    #[test]
+    pub fn test_addr_range() {
+        let start = va!(0x1000);
+        let range = start.range(0x2345);
+
+        assert_eq!(range.start, start);
+        assert_eq!(range.end, va!(0x3345));
+        assert_eq!(range.size(), 0x2345);
+        assert!(start.range(0).is_empty());
+        assert_eq!(va!(0).range(usize::MAX).size(), usize::MAX);
+    }
+
+    #[test]
+    pub fn test_addr_range_to() {
+        let start = va!(0x1000);
+        let range = start.range_to(va!(0x3345));
+        assert_eq!(range, start.range(0x2345));
+        assert!(start.range_to(start).is_empty());
+
+        assert_eq!(start.checked_range_to(va!(0x3345)), Some(range));
+        assert_eq!(start.checked_range_to(start), Some(start.range(0)));
+        assert_eq!(start.checked_range_to(va!(0xfff)), None);
+    }
+
+    #[test]
+    pub fn test_addr_checked_sub_size() {
+        let end = va!(0x3000);
+        assert_eq!(end.checked_sub_size(0x2000), Some(va!(0x1000)));
+        assert_eq!(end.checked_sub_size(0x3000), Some(va!(0)));
+        assert_eq!(end.checked_sub_size(0), Some(end));
+        assert_eq!(end.checked_sub_size(0x3001), None);
+        assert_eq!(va!(0).checked_sub_size(usize::MAX), None);
+
+        let start = end.checked_sub_size(0x2000).unwrap();
+        assert_eq!(start.range(0x2000).end, end);
+    }
+
+    #[test]
+    pub fn test_addr_covering_pages() {
+        // sub-page spans
+        assert_eq!(
+            va!(0x1234).covering_pages(0x10, 0x1000usize),
+            (va!(0x1000), va!(0x2000))
+        );
+        assert_eq!(
+            va!(0x1000).covering_pages(0x1000, 0x1000usize),
+            (va!(0x1000), va!(0x2000))
+        );
+        assert_eq!(
+            va!(0x1ff0).covering_pages(0x20, 0x1000usize),
+            (va!(0x1000), va!(0x3000))
+        );
+        // multi-page spans
+        assert_eq!(
+            va!(0x1234).covering_pages(0x3000, 0x1000usize),
+            (va!(0x1000), va!(0x5000))
+        );
+        assert_eq!(
+            va!(0x1234).covering_pages(0x40_0000, 0x20_0000usize),
+            (va!(0), va!(0x60_0000))
+        );
+        // empty regions
+        assert_eq!(
+            va!(0x1000).covering_pages(0, 0x1000usize),
+            (va!(0x1000), va!(0x1000))
+        );
+        assert_eq!(
+            va!(0x1234).covering_pages(0, 0x1000usize),
+            (va!(0x1000), va!(0x2000))
+        );
+
+        assert_eq!(
+            va!(0x1234).checked_covering_pages(0x3000, 0x1000usize),
+            Some((va!(0x1000), va!(0x5000)))
+        );
+        assert_eq!(
+            va!(usize::MAX - 0x1fff).checked_covering_pages(0x1000, 0x1000usize),
+            Some((va!(usize::MAX - 0x1fff), va!(usize::MAX - 0xfff)))
+        );
+        assert_eq!(
+            va!(usize::MAX - 0x1fff).checked_covering_pages(0x1001, 0x1000usize),
+            None
+        );
+        assert_eq!(va!(0x1234).checked_covering_pages(usize::MAX, 1usize), None);
+        assert_eq!(va!(0x1234).checked_covering_pages(0x10, 0x1001usize), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::covering_pages`")]
+    pub fn test_addr_covering_pages_overflow() {
+        let _ = va!(usize::MAX - 0xfff).covering_pages(0x10, 0x1000usize);
+    }
+
+    #[test]
+    pub fn test_addr_pack_base_len() {
+        for (base, len, len_bits) in [
+            (va!(0x1234_5000), 0, 12),
+            (va!(0x1234_5000), 0xfff, 12),
+            (va!(0x4000_0000), 0x3_ffff, 18),
+            (va!(0), 0, 0),
+            (va!(usize::MAX), 0, 0),
+            (
+                va!(1 << (usize::BITS - 1)),
+                usize::MAX >> 1,
+                usize::BITS - 1,
+            ),
+        ] {
+            let packed = base.pack_base_len(len, len_bits);
+            assert_eq!(VirtAddr::unpack_base_len(packed, len_bits), (base, len));
+        }
+        assert_eq!(va!(0x1234_5000).pack_base_len(3, 12), 0x1234_5003);
+    }
+
+    #[test]
+    #[should_panic(expected = "base not aligned in `MemoryAddr::pack_base_len`")]
+    pub fn test_addr_pack_base_len_unaligned() {
+        let _ = va!(0x1234_5800).pack_base_len(1, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "length too large in `MemoryAddr::pack_base_len`")]
+    pub fn test_addr_pack_base_len_too_long() {
+        let _ = va!(0x1234_5000).pack_base_len(0x1000, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "shift too large in `MemoryAddr::unpack_base_len`")]
+    pub fn test_addr_unpack_base_len_too_many_bits() {
+        let _ = VirtAddr::unpack_base_len(0, usize::BITS);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid `AddrRange`")]
+    pub fn test_addr_range_to_reversed() {
+        let _ = va!(0x2000).range_to(va!(0x1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::add`")]
+    pub fn test_addr_range_overflow() {
+        let _ = va!(0x1000).range(usize::MAX);
+    }
+
+    #[test]
+    pub fn test_addr_is_in_range() {
+        let start = ExampleAddr::from_usize(0x1000);
+        let end = ExampleAddr::from_usize(0x2000);
+
+        assert!(start.is_in_range(start, end));
+        assert!(!end.is_in_range(start, end));
+        assert!(ExampleAddr::from_usize(0x1fff).is_in_range(start, end));
+        assert!(!ExampleAddr::from_usize(0xfff).is_in_range(start, end));
+        assert!(!start.is_in_range(start, start));
+        assert!(!start.is_in_range(end, start));
+    }
+
+    #[test]
+    pub fn test_addr_clamp() {
+        let min = ExampleAddr::from_usize(0x1000);
+        let max = ExampleAddr::from_usize(0x2000);
+
+        assert_eq!(ExampleAddr::from_usize(0x800).clamp_addr(min, max), min);
+        assert_eq!(ExampleAddr::from_usize(0x3000).clamp_addr(min, max), max);
+        assert_eq!(
+            ExampleAddr::from_usize(0x1800).clamp_addr(min, max),
+            ExampleAddr::from_usize(0x1800)
+        );
+        assert_eq!(max.clamp_addr(min, max), max);
+    }
+
+    #[test]
+    pub fn test_iter_pages() {
+        let start = va!(0x1000);
+        let end = va!(0x4000);
 
-        assert_eq!(example1, ExampleAddr::from(0x1234));
-        assert_eq!(example2, ExampleAddr::from_usize(0x5678));
-        assert_eq!(another1, AnotherAddr::from_usize(0x9abc));
-        assert_eq!(another2, AnotherAddr::from(0xdef0));
+        let pages: Vec<_> = start.iter_pages_4k(end).collect();
+        assert_eq!(pages, [va!(0x1000), va!(0x2000), va!(0x3000)]);
+        let pages: Vec<_> = va!(0).iter_pages(va!(0x400000), 0x200000).collect();
+        assert_eq!(pages, [va!(0), va!(0x200000)]);
+        assert_eq!(start.iter_pages_4k(start).count(), 0);
 
-        assert!(example1 < example2);
-        assert!(example1 <= example2);
-        assert!(example2 > example1);
-        assert!(example2 >= example1);
-        assert!(example1 != example2);
+        let top = va!(usize::MAX - 0xfff);
+        let pages: Vec<_> = top.sub(0x1000).iter_pages_4k(top).collect();
+        assert_eq!(pages, [top.sub(0x1000)]);
     }
 
     #[test]
-    pub fn test_addr_fmt() {
-        assert_eq!(format!("{:?}", ExampleAddr::from(0x1abc)), "EA:0x1abc");
-        assert_eq!(format!("{:x}", AnotherAddr::from(0x1abc)), "AA:0x1abc");
-        assert_eq!(format!("{:X}", ExampleAddr::from(0x1abc)), "EA:0x1ABC");
+    #[should_panic]
+    pub fn test_iter_pages_unaligned() {
+        let _ = va!(0x1000).iter_pages_4k(va!(0x2001));
     }
 
     #[test]
-    pub fn test_alignment() {
-        let alignment = 0x1000usize;
-        let base = alignment * 2;
-        let offset = 0x123usize;
-        let addr = ExampleAddr::from_usize(base + offset);
+    #[should_panic]
+    pub fn test_iter_pages_reversed() {
+        let _ = va!(0x2000).iter_pages_4k(va!(0x1000));
+    }
 
-        assert_eq!(addr.align_down(alignment), ExampleAddr::from_usize(base));
-        assert_eq!(
-            addr.align_up(alignment),
-            ExampleAddr::from_usize(base + alignment)
-        );
-        assert_eq!(addr.align_offset(alignment), offset);
-        assert!(!addr.is_aligned(alignment));
-        assert!(ExampleAddr::from_usize(base).is_aligned(alignment));
-        assert_eq!(
-            ExampleAddr::from_usize(base).align_up(alignment),
-            ExampleAddr::from_usize(base)
-        );
+    #[test]
+    #[should_panic]
+    pub fn test_addr_offset_overflow() {
+        let addr = ExampleAddr::from_usize(usize::MAX);
+        let _ = addr.offset(1);
     }
 
     #[test]
-    pub fn test_addr_arithmetic() {
-        let base = 0x1234usize;
-        let offset = 0x100usize;
-        let with_offset = base + offset;
+    pub fn test_addr_wrapping_offset_from() {
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
 
-        let addr = ExampleAddr::from_usize(base);
-        let offset_addr = ExampleAddr::from_usize(with_offset);
+        assert_eq!(max.wrapping_offset_from(zero), -1);
+        assert_eq!(zero.wrapping_offset_from(max), 1);
+    }
 
-        assert_eq!(addr.offset(offset as isize), offset_addr);
-        assert_eq!(addr.wrapping_offset(offset as isize), offset_addr);
-        assert_eq!(offset_addr.offset_from(addr), offset as isize);
-        assert_eq!(addr.add(offset), offset_addr);
-        assert_eq!(addr.wrapping_add(offset), offset_addr);
-        assert_eq!(offset_addr.sub(offset), addr);
-        assert_eq!(offset_addr.wrapping_sub(offset), addr);
-        assert_eq!(offset_addr.sub_addr(addr), offset);
-        assert_eq!(offset_addr.wrapping_sub_addr(addr), offset);
+    #[test]
+    pub fn test_addr_overflowing_offset_from() {
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
+        let mid = ExampleAddr::from_usize(isize::MAX as usize);
 
-        assert_eq!(addr + offset, offset_addr);
-        assert_eq!(offset_addr - offset, addr);
-        assert_eq!(offset_addr - addr, offset);
+        assert_eq!(max.overflowing_offset_from(zero), (-1, true));
+        assert_eq!(zero.overflowing_offset_from(max), (1, true));
+        assert_eq!(mid.overflowing_offset_from(zero), (isize::MAX, false));
+        assert_eq!(zero.overflowing_offset_from(mid), (-isize::MAX, false));
+        assert_eq!(max.overflowing_offset_from(max), (0, false));
     }
 
     #[test]
-    pub fn test_addr_wrapping_arithmetic() {
-        let base = usize::MAX - 0x100usize;
-        let offset = 0x200usize;
-        let with_offset = base.wrapping_add(offset);
+    pub fn test_addr_checked_add_signed() {
+        let addr = ExampleAddr::from_usize(0x1000);
+        assert_eq!(
+            addr.checked_add_signed(0x10),
+            Some(ExampleAddr::from_usize(0x1010))
+        );
+        assert_eq!(
+            addr.checked_add_signed(-0x10),
+            Some(ExampleAddr::from_usize(0xff0))
+        );
 
-        let addr = ExampleAddr::from_usize(base);
-        let offset_addr = ExampleAddr::from_usize(with_offset);
+        let near_max = ExampleAddr::from_usize(usize::MAX - 1);
+        assert_eq!(
+            near_max.checked_add_signed(1),
+            Some(ExampleAddr::from_usize(usize::MAX))
+        );
+        assert_eq!(near_max.checked_add_signed(2), None);
+        assert_eq!(
+            near_max.checked_add_signed(isize::MIN),
+            Some(ExampleAddr::from_usize(isize::MAX as usize - 1))
+        );
 
-        assert_eq!(addr.wrapping_offset(offset as isize), offset_addr);
-        assert_eq!(offset_addr.wrapping_offset(-(offset as isize)), addr);
-        assert_eq!(addr.wrapping_add(offset), offset_addr);
-        assert_eq!(offset_addr.wrapping_sub(offset), addr);
-        assert_eq!(offset_addr.wrapping_sub_addr(addr), offset);
+        let near_zero = ExampleAddr::from_usize(1);
+        assert_eq!(
+            near_zero.checked_add_signed(-1),
+            Some(ExampleAddr::from_usize(0))
+        );
+        assert_eq!(near_zero.checked_add_signed(-2), None);
+        assert_eq!(near_zero.checked_add_signed(isize::MIN), None);
     }
 
     #[test]
-    pub fn test_addr_checked_arithmetic() {
-        let low_addr = ExampleAddr::from_usize(0x100usize);
-        let high_addr = ExampleAddr::from_usize(usize::MAX - 0x100usize);
-        let small_offset = 0x50usize;
-        let large_offset = 0x200usize;
+    pub fn test_addr_checked_offset_i64() {
+        let addr = ExampleAddr::from_usize(0x1000);
+        assert_eq!(
+            addr.checked_offset_i64(0x234),
+            Some(ExampleAddr::from_usize(0x1234))
+        );
+        assert_eq!(
+            addr.checked_offset_i64(-0x1000),
+            Some(ExampleAddr::from_usize(0))
+        );
+        assert_eq!(addr.checked_offset_i64(-0x1001), None);
+        assert_eq!(addr.checked_offset_i64(i64::MIN), None);
 
+        let max = ExampleAddr::from_usize(usize::MAX);
+        assert_eq!(max.checked_offset_i64(0), Some(max));
+        assert_eq!(max.checked_offset_i64(1), None);
+        assert_eq!(max.checked_offset_i64(i64::MAX), None);
         assert_eq!(
-            low_addr.checked_sub(small_offset),
-            Some(low_addr.wrapping_sub(small_offset))
+            max.checked_offset_i64(i64::MIN),
+            (usize::MAX as u64)
+                .checked_sub(1 << 63)
+                .map(|addr| ExampleAddr::from_usize(addr as usize))
         );
-        assert_eq!(low_addr.checked_sub(large_offset), None);
+
+        // The offset is not truncated to `usize` on 32-bit targets.
+        let offset = 1i64 << 40;
         assert_eq!(
-            high_addr.checked_add(small_offset),
-            Some(high_addr.wrapping_add(small_offset))
+            ExampleAddr::from_usize(0).checked_offset_i64(offset),
+            usize::try_from(offset).ok().map(ExampleAddr::from_usize)
         );
-        assert_eq!(high_addr.checked_add(large_offset), None);
+    }
+
+    #[test]
+    pub fn test_addr_checked_add_scaled() {
+        let base = ExampleAddr::from_usize(0x1000);
 
         assert_eq!(
-            high_addr.checked_sub_addr(low_addr),
-            Some(usize::MAX - 0x200usize)
+            base.checked_add_scaled(3, 0x10),
+            Some(ExampleAddr::from_usize(0x1030))
+        );
+        assert_eq!(base.checked_add_scaled(0, usize::MAX), Some(base));
+        // The multiplication overflows, even though the addition alone wouldn't.
+        assert_eq!(base.checked_add_scaled(usize::MAX / 2, 4), None);
+        // The multiplication doesn't overflow, but the addition does.
+        assert_eq!(base.checked_add_scaled(usize::MAX / 0x10, 0x10), None);
+        assert_eq!(
+            ExampleAddr::from_usize(0).checked_add_scaled(usize::MAX / 0x10, 0x10),
+            Some(ExampleAddr::from_usize(usize::MAX & !0xf))
         );
-        assert_eq!(low_addr.checked_sub_addr(high_addr), None);
     }
 
     #[test]
-    pub fn test_addr_overflowing_arithmetic() {
-        let low_addr = ExampleAddr::from_usize(0x100usize);
-        let high_addr = ExampleAddr::from_usize(usize::MAX - 0x100usize);
-        let small_offset = 0x50usize;
-        let large_offset = 0x200usize;
+    pub fn test_addr_saturating() {
+        let addr = ExampleAddr::from_usize(0x1000);
+        let max = ExampleAddr::from_usize(usize::MAX);
+
+        assert_eq!(addr.saturating_add(0x1000), ExampleAddr::from_usize(0x2000));
+        assert_eq!(addr.saturating_add(usize::MAX), max);
+        assert_eq!(max.saturating_add(1), max);
+
+        assert_eq!(addr.saturating_sub(0x800), ExampleAddr::from_usize(0x800));
+        assert_eq!(addr.saturating_sub(0x1001), ExampleAddr::from_usize(0));
+        assert_eq!(addr.saturating_sub(usize::MAX), ExampleAddr::from_usize(0));
+
+        assert_eq!(max.saturating_sub_addr(addr), usize::MAX - 0x1000);
+        assert_eq!(addr.saturating_sub_addr(max), 0);
+    }
 
+    #[test]
+    pub fn test_addr_index_of() {
+        let base = ExampleAddr::from_usize(0x1000);
+
+        assert_eq!(ExampleAddr::from_usize(0x1030).index_of(base, 0x10), 3);
+        assert_eq!(base.index_of(base, 0x10), 0);
+        let addr = base.checked_add_scaled(42, 0x18).unwrap();
+        assert_eq!(addr.index_of(base, 0x18), 42);
+        assert_eq!(addr.checked_index_of(base, 0x18), Some(42));
+
+        // misaligned
         assert_eq!(
-            low_addr.overflowing_sub(small_offset),
-            (low_addr.wrapping_sub(small_offset), false)
+            ExampleAddr::from_usize(0x1038).checked_index_of(base, 0x10),
+            None
         );
+        // below the base
         assert_eq!(
-            low_addr.overflowing_sub(large_offset),
-            (low_addr.wrapping_sub(large_offset), true)
+            ExampleAddr::from_usize(0xff0).checked_index_of(base, 0x10),
+            None
         );
+        // zero scale
         assert_eq!(
-            high_addr.overflowing_add(small_offset),
-            (high_addr.wrapping_add(small_offset), false)
+            ExampleAddr::from_usize(0x1030).checked_index_of(base, 0),
+            None
         );
+    }
+
+    #[test]
+    #[should_panic(expected = "misaligned address in `MemoryAddr::index_of`")]
+    pub fn test_addr_index_of_misaligned() {
+        let _ = ExampleAddr::from_usize(0x1038).index_of(ExampleAddr::from_usize(0x1000), 0x10);
+    }
+
+    #[test]
+    pub fn test_addr_abs_diff() {
+        let low = ExampleAddr::from_usize(0x1000);
+        let high = ExampleAddr::from_usize(0x3800);
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
+
+        assert_eq!(low.abs_diff(high), 0x2800);
+        assert_eq!(high.abs_diff(low), 0x2800);
+        assert_eq!(low.abs_diff(low), 0);
+        assert_eq!(max.abs_diff(zero), usize::MAX);
+        assert_eq!(zero.abs_diff(max), usize::MAX);
+    }
+
+    #[test]
+    pub fn test_addr_midpoint() {
+        let low = ExampleAddr::from_usize(0x1000);
+        let high = ExampleAddr::from_usize(0x3800);
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
+
+        assert_eq!(low.midpoint(high), ExampleAddr::from_usize(0x2400));
+        assert_eq!(high.midpoint(low), ExampleAddr::from_usize(0x2400));
+        assert_eq!(low.midpoint(low), low);
+        assert_eq!(low.midpoint(low + 1), low);
+
+        // no overflow near `usize::MAX`
+        assert_eq!(max.midpoint(max - 2), max - 1);
+        assert_eq!(max.midpoint(max), max);
+        assert_eq!(zero.midpoint(max), ExampleAddr::from_usize(usize::MAX / 2));
+    }
+
+    #[test]
+    pub fn test_addr_checked_offset_from() {
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
+        let mid = ExampleAddr::from_usize(isize::MAX as usize);
+
+        // The cases where `offset_from` panics.
+        assert_eq!(max.checked_offset_from(zero), None);
+        assert_eq!(zero.checked_offset_from(max), None);
+        assert_eq!(max.checked_offset_from(mid), None);
+
+        assert_eq!(mid.checked_offset_from(zero), Some(isize::MAX));
+        assert_eq!(zero.checked_offset_from(mid), Some(-isize::MAX));
+        assert_eq!(max.checked_offset_from(max), Some(0));
+    }
+
+    #[test]
+    pub fn test_addr_offset_into() {
+        let region_start = pa!(0x8000_0000);
+
+        assert_eq!(pa!(0x8000_1234).offset_into(region_start), 0x1234);
+        assert_eq!(region_start.offset_into(region_start), 0);
         assert_eq!(
-            high_addr.overflowing_add(large_offset),
-            (high_addr.wrapping_add(large_offset), true)
+            pa!(0x8000_1234).checked_offset_into(region_start),
+            Some(0x1234)
         );
+        assert_eq!(pa!(0x7fff_ffff).checked_offset_into(region_start), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "address below the region start in `MemoryAddr::offset_into`")]
+    pub fn test_addr_offset_into_out_of_region() {
+        let _ = pa!(0x7fff_ffff).offset_into(pa!(0x8000_0000));
+    }
+
+    #[test]
+    pub fn test_addr_wrapping() {
+        use core::num::Wrapping;
+
+        let addr = ExampleAddr::from_usize(0x1234);
+        assert_eq!(addr.as_wrapping(), Wrapping(0x1234));
+        assert_eq!(ExampleAddr::from_wrapping(addr.as_wrapping()), addr);
+
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let result = max.as_wrapping() + Wrapping(0x1001) - Wrapping(0x10usize);
         assert_eq!(
-            high_addr.overflowing_sub_addr(low_addr),
-            (high_addr.wrapping_sub_addr(low_addr), false)
+            ExampleAddr::from_wrapping(result),
+            ExampleAddr::from_usize(0xff0)
         );
         assert_eq!(
-            low_addr.overflowing_sub_addr(high_addr),
-            (low_addr.wrapping_sub_addr(high_addr), true)
+            ExampleAddr::from_wrapping(result),
+            max.wrapping_add(0x1001).wrapping_sub(0x10)
         );
     }
 
     #[test]
-    #[should_panic]
-    pub fn test_addr_offset_overflow() {
-        let addr = ExampleAddr::from_usize(usize::MAX);
-        let _ = addr.offset(1);
+    pub fn test_addr_saturating_offset_from() {
+        let max = ExampleAddr::from_usize(usize::MAX);
+        let zero = ExampleAddr::from_usize(0);
+        let mid = ExampleAddr::from_usize(isize::MAX as usize);
+
+        assert_eq!(max.saturating_offset_from(zero), isize::MAX);
+        assert_eq!(zero.saturating_offset_from(max), isize::MIN);
+        assert_eq!(mid.saturating_offset_from(zero), isize::MAX);
+        assert_eq!(zero.saturating_offset_from(mid), -isize::MAX);
+        assert_eq!(max.saturating_offset_from(mid), isize::MAX);
+        assert_eq!(max.saturating_offset_from(max), 0);
     }
 
     #[test]
@@ -736,19 +4651,264 @@ mod test {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "overflow in `MemoryAddr::sub`: 0x0 - 0x1")]
     pub fn test_addr_sub_underflow() {
         let addr = ExampleAddr::from_usize(0);
         let _ = addr.sub(1);
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "overflow in `MemoryAddr::sub_addr`: 0x0 - 0x1")]
     pub fn test_addr_sub_addr_overflow() {
         let addr = ExampleAddr::from_usize(0);
         let _ = addr.sub_addr(ExampleAddr::from_usize(1));
     }
 
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "overflow in `MemoryAddr::add`: 0xffffffffffffff00 + 0x100")]
+    pub fn test_addr_add_overflow_message() {
+        let _ = ExampleAddr::from_usize(0xffff_ffff_ffff_ff00).add(0x100);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::offset`: 0x1000 - 0x1001")]
+    pub fn test_addr_offset_underflow_message() {
+        let _ = ExampleAddr::from_usize(0x1000).offset(-0x1001);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "overflow in `MemoryAddr::offset`: 0xfffffffffffff000 + 0x1000")]
+    pub fn test_addr_offset_overflow_message() {
+        let _ = ExampleAddr::from_usize(0xffff_ffff_ffff_f000).offset(0x1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::prev_aligned`: 0x0 aligned to 0x10")]
+    pub fn test_addr_prev_aligned_underflow_message() {
+        let _ = ExampleAddr::from_usize(0).prev_aligned(0x10usize);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "overflow in `MemoryAddr::offset_from`: 0x0 - 0xffffffffffffffff")]
+    pub fn test_addr_offset_from_overflow_message() {
+        let _ = ExampleAddr::from_usize(0).offset_from(ExampleAddr::from_usize(usize::MAX));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(
+        expected = "overflow in `MemoryAddr::from_page_number_4k`: 0x10000000000000 * 0x1000"
+    )]
+    pub fn test_addr_from_page_number_4k_overflow_message() {
+        let _ = ExampleAddr::from_page_number_4k(1 << 52);
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    pub fn test_addr_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes = [0x12u8, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+        let expected = usize::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+
+        let mut u = Unstructured::new(&bytes);
+        assert_eq!(
+            ExampleAddr::arbitrary(&mut u).unwrap(),
+            ExampleAddr::from_usize(expected)
+        );
+        let mut u = Unstructured::new(&bytes);
+        assert_eq!(
+            PhysAddr::arbitrary(&mut u).unwrap(),
+            PhysAddr::from_usize(expected)
+        );
+        assert_eq!(ExampleAddr::size_hint(0), usize::size_hint(0));
+    }
+
+    #[test]
+    #[cfg(feature = "num-traits")]
+    pub fn test_addr_num_traits() {
+        use num_traits::Bounded;
+
+        assert_eq!(
+            <ExampleAddr as Bounded>::min_value(),
+            ExampleAddr::from_usize(0)
+        );
+        assert_eq!(
+            <ExampleAddr as Bounded>::max_value(),
+            ExampleAddr::from_usize(usize::MAX)
+        );
+        assert_eq!(PhysAddr::min_value(), PhysAddr::min_addr());
+        assert_eq!(VirtAddr::max_value(), VirtAddr::max_addr());
+    }
+
+    #[test]
+    pub fn test_addr_eq_usize() {
+        assert_eq!(CmpAddr::from(0x1000), 0x1000usize);
+        assert_eq!(0x1000usize, CmpAddr::from(0x1000));
+        assert_ne!(CmpAddr::from(0x1000), 0x1001usize);
+        assert_ne!(0x1001usize, CmpAddr::from(0x1000));
+        assert!(CmpAddr::from_usize(usize::MAX) == usize::MAX);
+
+        // Literals without suffixes are still inferred as `usize`.
+        assert!(CmpAddr::from(0x1000) == 0x1000);
+        assert!(0x1000 == CmpAddr::from(0x1000));
+    }
+
+    #[test]
+    pub fn test_addr_cmp_usize() {
+        let addr = CmpAddr::from(0x1000);
+        assert!(addr < 0x1001usize);
+        assert!(addr <= 0x1000usize);
+        assert!(addr > 0xfffusize);
+        assert!(addr >= 0x1000usize);
+
+        assert!(0xfffusize < addr);
+        assert!(0x1000usize <= addr);
+        assert!(0x1001usize > addr);
+        assert!(0x1000usize >= addr);
+
+        assert!(CmpAddr::from(0) < 1);
+        assert!(usize::MAX > CmpAddr::from(0));
+        assert_eq!(
+            CmpAddr::from(0x1000).partial_cmp(&0x1000usize),
+            Some(core::cmp::Ordering::Equal)
+        );
+    }
+
+    /// A `defmt` logger capturing the raw (unencoded) bytes of the frames.
+    #[cfg(feature = "defmt")]
+    mod defmt_logger {
+        use std::sync::Mutex;
+
+        pub static BYTES: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+        #[defmt::global_logger]
+        struct Logger;
+
+        defmt::timestamp!("");
+
+        unsafe impl defmt::Logger for Logger {
+            fn acquire() {}
+            unsafe fn flush() {}
+            unsafe fn release() {}
+            unsafe fn write(bytes: &[u8]) {
+                BYTES.lock().unwrap().extend_from_slice(bytes);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "defmt")]
+    pub fn test_addr_defmt() {
+        fn contains(bytes: &[u8], pattern: &[u8]) -> bool {
+            bytes.windows(pattern.len()).any(|w| w == pattern)
+        }
+
+        defmt::println!("{}", pa!(0x1234_5678_9abc));
+        let bytes = core::mem::take(&mut *defmt_logger::BYTES.lock().unwrap());
+
+        // The prefix is sent as a string, and the address as a raw 64-bit
+        // integer rather than a formatted string.
+        assert!(contains(&bytes, b"PA:"));
+        assert!(contains(&bytes, &0x1234_5678_9abc_u64.to_le_bytes()));
+        assert!(!contains(&bytes, b"0x123456789abc"));
+
+        assert_eq!(__split_addr_format("PA:{}"), ("PA:", ""));
+        assert_eq!(
+            __split_addr_format("ExampleAddr({})"),
+            ("ExampleAddr(", ")")
+        );
+    }
+
+    #[test]
+    pub fn test_addr_try_into_u32() {
+        assert_eq!(u32::try_from(pa!(0)), Ok(0));
+        assert_eq!(u32::try_from(pa!(0x8000_1000)), Ok(0x8000_1000));
+        assert_eq!(u32::try_from(pa!(u32::MAX as usize)), Ok(u32::MAX));
+        if let Some(addr) = (u32::MAX as usize).checked_add(1) {
+            assert!(u32::try_from(pa!(addr)).is_err());
+            assert!(u32::try_from(va!(usize::MAX)).is_err());
+        }
+
+        let reg: Result<u32, _> = ExampleAddr::from_usize(0x1000).try_into();
+        assert_eq!(reg, Ok(0x1000));
+    }
+
+    #[test]
+    pub fn test_addr_null() {
+        assert!(VirtAddr::NULL.is_null());
+        assert_eq!(
+            VirtAddr::NULL,
+            VirtAddr::from_ptr_of(core::ptr::null::<u8>())
+        );
+        assert!(VirtAddr::NULL.as_ptr().is_null());
+        assert!(!va!(0x1000).is_null());
+        assert!(ExampleAddr::from_usize(0).is_null());
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    pub fn test_addr_bytemuck() {
+        let addrs = [pa!(0x1000), pa!(0x2000), pa!(0x3000)];
+        let raw: &[usize] = bytemuck::cast_slice(&addrs);
+        assert_eq!(raw, &[0x1000, 0x2000, 0x3000]);
+        let back: &[PhysAddr] = bytemuck::cast_slice(raw);
+        assert_eq!(back, &addrs);
+        assert_eq!(
+            <ExampleAddr as bytemuck::Zeroable>::zeroed(),
+            ExampleAddr::from_usize(0)
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    pub fn test_virt_addr_canonical() {
+        assert!(va!(0).is_canonical_with_bits(48));
+        assert!(va!(0x0000_7fff_ffff_ffff).is_canonical_with_bits(48));
+        assert!(va!(0xffff_8000_0000_0000).is_canonical_with_bits(48));
+        assert!(va!(usize::MAX).is_canonical_with_bits(48));
+        assert!(!va!(0x0000_8000_0000_0000).is_canonical_with_bits(48));
+        assert!(!va!(0xfff0_0000_0000_1000).is_canonical_with_bits(48));
+
+        // RISC-V Sv39.
+        assert!(va!(0x0000_003f_ffff_ffff).is_canonical_with_bits(39));
+        assert!(va!(0xffff_ffc0_0000_0000).is_canonical_with_bits(39));
+        assert!(!va!(0x0000_0040_0000_0000).is_canonical_with_bits(39));
+        assert!(!va!(0x0000_7fff_ffff_ffff).is_canonical_with_bits(39));
+
+        // x86_64 5-level paging and RISC-V Sv57.
+        assert!(va!(0x00ff_ffff_ffff_ffff).is_canonical_with_bits(57));
+        assert!(va!(0xff00_0000_0000_0000).is_canonical_with_bits(57));
+        assert!(!va!(0x0100_0000_0000_0000).is_canonical_with_bits(57));
+
+        assert!(va!(0x8000_0000_0000_0000).is_canonical_with_bits(64));
+        assert_eq!(
+            va!(0x0000_8000_0000_0000).is_canonical(),
+            VirtAddr::CANONICAL_BITS > 48
+        );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "canonical_check",
+        debug_assertions,
+        target_pointer_width = "64"
+    ))]
+    #[should_panic(expected = "non-canonical")]
+    pub fn test_virt_addr_non_canonical_ptr() {
+        let _ = va!(0x8000_0000_0000_0000).as_ptr();
+    }
+
+    #[test]
+    #[cfg(all(feature = "canonical_check", debug_assertions))]
+    #[should_panic(expected = "low tag bits")]
+    pub fn test_virt_addr_tagged_ptr() {
+        let _ = va!(0x1001).as_ptr_of::<u32>();
+    }
+
     #[test]
     pub fn test_virt_addr_ptr() {
         let a: [usize; 4] = [0x1234, 0x5678, 0x9abc, 0xdef0];
@@ -769,6 +4929,11 @@ mod test {
         assert_eq!(va2, VirtAddr::from_mut_ptr_of(p2));
         assert_eq!(va3, VirtAddr::from_mut_ptr_of(p3));
 
+        assert_eq!(va0, VirtAddr::from(p0));
+        assert_eq!(va3, VirtAddr::from(p3));
+        let va: VirtAddr = a.as_ptr().into();
+        assert_eq!(va, va0);
+
         // testing pointer read/write
         assert!(unsafe { *p0 } == a[0]);
         assert!(unsafe { *p1 } == a[1]);
@@ -0,0 +1,137 @@
+use core::{fmt, ops::Deref};
+
+use crate::MemoryAddr;
+
+/// An address of type `A` that is guaranteed to be aligned to `ALIGN`.
+///
+/// It's a zero-cost wrapper that can only be constructed via
+/// [`Aligned::new`], which checks the alignment, or the unsafe
+/// [`Aligned::new_unchecked`]. Functions taking it don't need to check the
+/// alignment again.
+///
+/// `ALIGN` must be a power of two, which is checked at compile time.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{pa, Aligned4K, PhysAddr};
+///
+/// fn map_page(paddr: Aligned4K<PhysAddr>) -> usize {
+///     // `paddr` is known to be 4K-aligned here.
+///     paddr.as_usize() >> 12
+/// }
+///
+/// let paddr = Aligned4K::new(pa!(0x1000)).unwrap();
+/// assert_eq!(map_page(paddr), 1);
+/// assert!(Aligned4K::new(pa!(0x1234)).is_none());
+/// ```
+///
+/// And this won't compile, since the alignment is not a power of two:
+///
+/// ```compile_fail
+/// # use memory_addr::Aligned;
+/// let _ = Aligned::<usize, 0x1001>::new(0x1001);
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aligned<A: MemoryAddr, const ALIGN: usize>(A);
+
+/// An address of type `A` that is guaranteed to be 4K-aligned.
+pub type Aligned4K<A> = Aligned<A, { crate::PAGE_SIZE_4K }>;
+
+impl<A, const ALIGN: usize> Aligned<A, ALIGN>
+where
+    A: MemoryAddr,
+{
+    const VALID_ALIGN: () = assert!(ALIGN.is_power_of_two(), "alignment must be a power of two");
+
+    /// Wraps the address, returning `None` if it's not aligned to `ALIGN`.
+    #[inline]
+    pub fn new(addr: A) -> Option<Self> {
+        let () = Self::VALID_ALIGN;
+        if addr.is_aligned(ALIGN) {
+            Some(Self(addr))
+        } else {
+            None
+        }
+    }
+
+    /// Wraps the address without checking the alignment.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be aligned to `ALIGN`.
+    #[inline]
+    pub unsafe fn new_unchecked(addr: A) -> Self {
+        let () = Self::VALID_ALIGN;
+        debug_assert!(addr.is_aligned(ALIGN), "address is not aligned");
+        Self(addr)
+    }
+
+    /// Returns the wrapped address.
+    #[inline]
+    pub fn get(self) -> A {
+        self.0
+    }
+}
+
+impl<A, const ALIGN: usize> Deref for Aligned<A, ALIGN>
+where
+    A: MemoryAddr,
+{
+    type Target = A;
+
+    #[inline]
+    fn deref(&self) -> &A {
+        &self.0
+    }
+}
+
+impl<A, const ALIGN: usize> fmt::Debug for Aligned<A, ALIGN>
+where
+    A: MemoryAddr + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{pa, va, Aligned, Aligned4K, MemoryAddr};
+
+    #[test]
+    fn test_aligned() {
+        let aligned = Aligned4K::new(va!(0x1234_5000)).unwrap();
+        assert_eq!(aligned.get(), va!(0x1234_5000));
+        assert_eq!(*aligned, va!(0x1234_5000));
+        assert!(aligned.is_aligned_4k());
+        assert_eq!(format!("{:?}", aligned), "VA:0x12345000");
+
+        assert!(Aligned4K::new(va!(0x1234_5678)).is_none());
+        assert!(Aligned::<_, 0x20_0000>::new(pa!(0x1234_5000)).is_none());
+        assert!(Aligned::<_, 0x20_0000>::new(pa!(0x1220_0000)).is_some());
+        assert!(Aligned::<_, 1>::new(0x1234_5678usize).is_some());
+
+        // SAFETY: the address is 4K-aligned.
+        let unchecked = unsafe { Aligned4K::new_unchecked(va!(0x1234_5000)) };
+        assert_eq!(unchecked, aligned);
+
+        assert_eq!(
+            core::mem::size_of::<Aligned4K<usize>>(),
+            core::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_try_aligned() {
+        let aligned = va!(0x1234_5000).try_aligned::<0x1000>().unwrap();
+        assert_eq!(aligned, Aligned4K::new(va!(0x1234_5000)).unwrap());
+        assert_eq!(aligned.get(), va!(0x1234_5000));
+
+        assert!(va!(0x1234_5678).try_aligned::<0x1000>().is_none());
+        assert!(pa!(0x1234_5000).try_aligned::<0x20_0000>().is_none());
+        assert!(pa!(0x1220_0000).try_aligned::<0x20_0000>().is_some());
+        assert!(0x1234_5678usize.try_aligned::<1>().is_some());
+    }
+}
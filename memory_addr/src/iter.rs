@@ -63,3 +63,100 @@ where
         }
     }
 }
+
+/// An iterator over addresses with a custom stride, e.g., cache-line-sized
+/// probes.
+///
+/// It yields `start`, `start + stride`, `start + 2 * stride`, ..., as long as
+/// the address is less than `end`. Unlike [`PageIter`], the addresses don't
+/// need to be aligned, and the stride doesn't need to be a power of 2.
+///
+/// # Examples
+///
+/// ```
+/// use memory_addr::StrideIter;
+///
+/// // The stride divides the range length.
+/// let iter = StrideIter::new(0x1000usize, 0x1100, 0x40).unwrap();
+/// assert!(iter.eq([0x1000, 0x1040, 0x1080, 0x10c0]));
+///
+/// // The stride doesn't divide the range length.
+/// let iter = StrideIter::new(0x1000usize, 0x1100, 0x60).unwrap();
+/// assert!(iter.eq([0x1000, 0x1060, 0x10c0]));
+///
+/// assert!(StrideIter::new(0x1000usize, 0x1100, 0).is_none());
+/// ```
+pub struct StrideIter<A>
+where
+    A: MemoryAddr,
+{
+    start: A,
+    end: A,
+    stride: usize,
+}
+
+impl<A> StrideIter<A>
+where
+    A: MemoryAddr,
+{
+    /// Creates a new [`StrideIter`].
+    ///
+    /// Returns `None` if `stride` is zero.
+    pub fn new(start: A, end: A, stride: usize) -> Option<Self> {
+        if stride == 0 {
+            None
+        } else {
+            Some(Self { start, end, stride })
+        }
+    }
+}
+
+impl<A> Iterator for StrideIter<A>
+where
+    A: MemoryAddr,
+{
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            let ret = self.start;
+            // Stop at `end` if the next address overflows.
+            self.start = self.start.checked_add(self.stride).unwrap_or(self.end);
+            Some(ret)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{va, StrideIter};
+
+    #[test]
+    fn test_stride_iter() {
+        let iter = StrideIter::new(va!(0x1000), va!(0x1100), 0x40).unwrap();
+        assert!(iter.eq([va!(0x1000), va!(0x1040), va!(0x1080), va!(0x10c0)]));
+
+        let iter = StrideIter::new(va!(0x1001), va!(0x1100), 0x60).unwrap();
+        assert!(iter.eq([va!(0x1001), va!(0x1061), va!(0x10c1)]));
+
+        assert_eq!(
+            StrideIter::new(va!(0x1000), va!(0x1000), 1)
+                .unwrap()
+                .count(),
+            0
+        );
+        assert_eq!(
+            StrideIter::new(va!(0x2000), va!(0x1000), 1)
+                .unwrap()
+                .count(),
+            0
+        );
+        assert!(StrideIter::new(va!(0x1000), va!(0x2000), 0).is_none());
+
+        // terminates at the top of the address space
+        let iter = StrideIter::new(va!(usize::MAX - 0x20), va!(usize::MAX), 0x18).unwrap();
+        assert!(iter.eq([va!(usize::MAX - 0x20), va!(usize::MAX - 0x8)]));
+    }
+}
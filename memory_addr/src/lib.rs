@@ -1,17 +1,63 @@
 #![cfg_attr(not(test), no_std)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 mod addr;
+mod aligned;
 mod iter;
 mod range;
+#[cfg(feature = "alloc")]
+mod range_set;
+mod space;
+
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub use self::addr::__split_addr_format;
+pub use self::addr::{AlignError, MemoryAddr, PhysAddr, VirtAddr};
+pub use self::aligned::{Aligned, Aligned4K};
+pub use self::iter::{PageIter, StrideIter};
+pub use self::range::{
+    region_contains, AddrRange, AddrRangeInclusive, PhysAddrRange, VirtAddrRange,
+};
+#[cfg(feature = "alloc")]
+pub use self::range_set::AddrRangeSet;
+pub use self::space::{AddrSpace, HasAddrSpace, PhysAddrSpace, VirtAddrSpace};
+
+/// Derives the conversions and operators of an address type, see
+/// [`memory_addr_derive`](https://docs.rs/memory_addr_derive) for details.
+///
+/// This macro is only available with the `derive` feature.
+#[cfg(feature = "derive")]
+pub use memory_addr_derive::MemoryAddr;
 
-pub use self::addr::{MemoryAddr, PhysAddr, VirtAddr};
-pub use self::iter::PageIter;
-pub use self::range::{AddrRange, PhysAddrRange, VirtAddrRange};
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+pub use arbitrary;
+#[cfg(feature = "bytemuck")]
+#[doc(hidden)]
+pub use bytemuck;
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+pub use defmt;
+#[cfg(feature = "num-traits")]
+#[doc(hidden)]
+pub use num_traits;
 
 /// The size of a 4K page (4096 bytes).
 pub const PAGE_SIZE_4K: usize = 0x1000;
 
+/// The number of bits to shift an address to get its 4K page number, i.e.,
+/// `log2(PAGE_SIZE_4K)`.
+pub const PAGE_SHIFT_4K: usize = 12;
+
+/// The size of a 2M superpage (2 MiB).
+pub const PAGE_SIZE_2M: usize = 0x20_0000;
+
+/// The size of a 1G superpage (1 GiB).
+pub const PAGE_SIZE_1G: usize = 0x4000_0000;
+
 /// A [`PageIter`] for 4K pages.
 pub type PageIter4K<A> = PageIter<PAGE_SIZE_4K, A>;
 
@@ -19,9 +65,10 @@ pub type PageIter4K<A> = PageIter<PAGE_SIZE_4K, A>;
 ///
 /// Returns the greatest `x` with alignment `align` so that `x <= addr`.
 ///
-/// The alignment must be a power of two.
+/// The alignment must be a power of two, which is checked in debug builds.
 #[inline]
 pub const fn align_down(addr: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
     addr & !(align - 1)
 }
 
@@ -29,25 +76,30 @@ pub const fn align_down(addr: usize, align: usize) -> usize {
 ///
 /// Returns the smallest `x` with alignment `align` so that `x >= addr`.
 ///
-/// The alignment must be a power of two.
+/// The alignment must be a power of two, which is checked in debug builds.
 #[inline]
 pub const fn align_up(addr: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
     (addr + align - 1) & !(align - 1)
 }
 
 /// Returns the offset of the address within the alignment.
 ///
-/// Equivalent to `addr % align`, but the alignment must be a power of two.
+/// Equivalent to `addr % align`, but the alignment must be a power of two,
+/// which is checked in debug builds.
 #[inline]
 pub const fn align_offset(addr: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
     addr & (align - 1)
 }
 
 /// Checks whether the address has the demanded alignment.
 ///
-/// Equivalent to `addr % align == 0`, but the alignment must be a power of two.
+/// Equivalent to `addr % align == 0`, but the alignment must be a power of
+/// two, which is checked in debug builds.
 #[inline]
 pub const fn is_aligned(addr: usize, align: usize) -> bool {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
     align_offset(addr, align) == 0
 }
 
@@ -75,6 +127,150 @@ pub const fn is_aligned_4k(addr: usize) -> bool {
     is_aligned(addr, PAGE_SIZE_4K)
 }
 
+/// Converts a signed offset to a length, returning `None` if it's negative.
+#[inline]
+pub const fn offset_to_usize(off: isize) -> Option<usize> {
+    if off >= 0 {
+        Some(off as usize)
+    } else {
+        None
+    }
+}
+
+/// Converts a length to a signed offset, returning `None` if it's greater
+/// than `isize::MAX`.
+#[inline]
+pub const fn usize_to_offset(len: usize) -> Option<isize> {
+    if len <= isize::MAX as usize {
+        Some(len as isize)
+    } else {
+        None
+    }
+}
+
+/// Returns the number of pages of size `page_size` needed to hold `size`
+/// bytes, i.e., `size / page_size` rounded up.
+///
+/// Unlike `align_up(size, page_size) / page_size`, it never overflows, even if
+/// `size` is close to `usize::MAX`.
+///
+/// # Panics
+///
+/// Panics if `page_size` is zero.
+#[inline]
+pub const fn page_count(size: usize, page_size: usize) -> usize {
+    size.div_ceil(page_size)
+}
+
+/// Returns the number of 4K pages needed to hold `size` bytes.
+#[inline]
+pub const fn page_count_4k(size: usize) -> usize {
+    page_count(size, PAGE_SIZE_4K)
+}
+
+/// Returns a human-readable representation of a size in bytes, e.g., `4 KiB`
+/// for `4096`, which implements [`Display`](core::fmt::Display).
+///
+/// The largest binary unit (`B`, `KiB`, `MiB`, ..., `EiB`) that divides the
+/// size is used, so that the size is never rounded, e.g., `1536 KiB` rather
+/// than `1.5 MiB`. It writes to the formatter directly without allocation.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::format_size;
+///
+/// assert_eq!(format_size(4096).to_string(), "4 KiB");
+/// assert_eq!(format_size(0x180000).to_string(), "1536 KiB");
+/// assert_eq!(format_size(4097).to_string(), "4097 B");
+/// ```
+pub fn format_size(bytes: usize) -> impl core::fmt::Display {
+    struct Size(usize);
+
+    impl core::fmt::Display for Size {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+            let (mut value, mut unit) = (self.0, 0);
+            while value != 0 && value % 1024 == 0 && unit < UNITS.len() - 1 {
+                value /= 1024;
+                unit += 1;
+            }
+            write!(f, "{} {}", value, UNITS[unit])
+        }
+    }
+
+    Size(bytes)
+}
+
+/// A wrapper to format an address in hexadecimal with its digits grouped by
+/// `_` every 4 digits, e.g., `0x1234_5678`, which is easier to read for large
+/// 64-bit addresses.
+///
+/// [`Display`](core::fmt::Display) always prints the `0x` prefix, while
+/// [`LowerHex`](core::fmt::LowerHex) prints it only with the `#` flag, like
+/// integers do. Leading zeros are not printed.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{va, GroupedHex};
+///
+/// let addr = va!(0xffff_8000_1234_5678);
+/// assert_eq!(GroupedHex(addr).to_string(), "0xffff_8000_1234_5678");
+/// assert_eq!(format!("{:x}", GroupedHex(0x12345usize)), "1_2345");
+/// assert_eq!(format!("{:#x}", GroupedHex(0x12345usize)), "0x1_2345");
+/// ```
+#[derive(Clone, Copy)]
+pub struct GroupedHex<A: MemoryAddr>(pub A);
+
+impl<A: MemoryAddr> GroupedHex<A> {
+    fn write_digits(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+
+        let addr = self.0.into();
+        let digits = ((usize::BITS - addr.leading_zeros()).div_ceil(4)).max(1);
+        for i in (0..digits).rev() {
+            let digit = (addr >> (i * 4)) & 0xf;
+            f.write_char(core::char::from_digit(digit as u32, 16).unwrap())?;
+            if i != 0 && i % 4 == 0 {
+                f.write_char('_')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<A: MemoryAddr> core::fmt::Display for GroupedHex<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("0x")?;
+        self.write_digits(f)
+    }
+}
+
+impl<A: MemoryAddr> core::fmt::LowerHex for GroupedHex<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        self.write_digits(f)
+    }
+}
+
+/// Returns the size of a block of `2^order` 4K pages, as used in buddy
+/// allocators, i.e., `PAGE_SIZE_4K << order`.
+///
+/// # Panics
+///
+/// Panics if the size is not representable by `usize`.
+#[inline]
+pub const fn page_size_of_order(order: u32) -> usize {
+    assert!(
+        order < usize::BITS - PAGE_SHIFT_4K as u32,
+        "order too large in `page_size_of_order`"
+    );
+    PAGE_SIZE_4K << order
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,5 +288,120 @@ mod tests {
         assert_eq!(align_offset_4k(0x12345678), 0x678);
         assert!(is_aligned_4k(0x12345000));
         assert!(!is_aligned_4k(0x12345678));
+        assert_eq!(1 << PAGE_SHIFT_4K, PAGE_SIZE_4K);
+    }
+
+    #[test]
+    fn test_offset_usize_conversion() {
+        assert_eq!(offset_to_usize(0), Some(0));
+        assert_eq!(offset_to_usize(0x1000), Some(0x1000));
+        assert_eq!(offset_to_usize(isize::MAX), Some(isize::MAX as usize));
+        assert_eq!(offset_to_usize(-1), None);
+        assert_eq!(offset_to_usize(isize::MIN), None);
+
+        assert_eq!(usize_to_offset(0), Some(0));
+        assert_eq!(usize_to_offset(0x1000), Some(0x1000));
+        assert_eq!(usize_to_offset(isize::MAX as usize), Some(isize::MAX));
+        assert_eq!(usize_to_offset(isize::MAX as usize + 1), None);
+        assert_eq!(usize_to_offset(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0, 0x1000), 0);
+        assert_eq!(page_count(0x1000, 0x1000), 1);
+        assert_eq!(page_count(0x3000, 0x1000), 3);
+        assert_eq!(page_count(0x3001, 0x1000), 4);
+        assert_eq!(page_count(0x2fff, 0x1000), 3);
+        assert_eq!(page_count(0x3000, 0x200000), 1);
+        assert_eq!(page_count(usize::MAX, 0x1000), (usize::MAX >> 12) + 1);
+
+        assert_eq!(page_count_4k(0), 0);
+        assert_eq!(page_count_4k(1), 1);
+        assert_eq!(page_count_4k(0x1000), 1);
+        assert_eq!(page_count_4k(0x1001), 2);
+        assert_eq!(page_count_4k(usize::MAX - 0xfff), usize::MAX >> 12);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0).to_string(), "0 B");
+        assert_eq!(format_size(1).to_string(), "1 B");
+        assert_eq!(format_size(1023).to_string(), "1023 B");
+        assert_eq!(format_size(1024).to_string(), "1 KiB");
+        assert_eq!(format_size(4096).to_string(), "4 KiB");
+        assert_eq!(format_size(1025).to_string(), "1025 B");
+        assert_eq!(format_size(1024 * 1024 - 1024).to_string(), "1023 KiB");
+        assert_eq!(format_size(1024 * 1024).to_string(), "1 MiB");
+        assert_eq!(format_size(2 * 1024 * 1024).to_string(), "2 MiB");
+        assert_eq!(format_size(1024 * 1024 * 1024).to_string(), "1 GiB");
+        assert_eq!(format_size(3 << 30 | 1 << 20).to_string(), "3073 MiB");
+        assert_eq!(format!("{:>8}", format_size(4096).to_string()), "   4 KiB");
+    }
+
+    #[test]
+    fn test_grouped_hex() {
+        assert_eq!(GroupedHex(0usize).to_string(), "0x0");
+        assert_eq!(GroupedHex(0xabcdusize).to_string(), "0xabcd");
+        assert_eq!(GroupedHex(0x1_0000usize).to_string(), "0x1_0000");
+        assert_eq!(
+            GroupedHex(va!(0x1234_5678_9abc)).to_string(),
+            "0x1234_5678_9abc"
+        );
+        assert_eq!(
+            GroupedHex(pa!(usize::MAX)).to_string(),
+            if usize::BITS == 64 {
+                "0xffff_ffff_ffff_ffff"
+            } else {
+                "0xffff_ffff"
+            }
+        );
+
+        assert_eq!(
+            format!("{:x}", GroupedHex(va!(0x8_0000_1000))),
+            "8_0000_1000"
+        );
+        assert_eq!(
+            format!("{:#x}", GroupedHex(va!(0x8_0000_1000))),
+            "0x8_0000_1000"
+        );
+    }
+
+    #[test]
+    fn test_page_size_of_order() {
+        assert_eq!(page_size_of_order(0), PAGE_SIZE_4K);
+        assert_eq!(page_size_of_order(1), 0x2000);
+        assert_eq!(page_size_of_order(9), 0x200000);
+        assert_eq!(
+            page_size_of_order(usize::BITS - PAGE_SHIFT_4K as u32 - 1),
+            1 << (usize::BITS - 1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_page_size_of_order_overflow() {
+        let _ = page_size_of_order(usize::BITS - PAGE_SHIFT_4K as u32);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "alignment must be a power of two")]
+    fn test_align_down_not_power_of_two() {
+        let _ = align_down(0x12345678, 0x1001);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "alignment must be a power of two")]
+    fn test_align_up_not_power_of_two() {
+        let _ = align_up(0x12345678, 0x1001);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "alignment must be a power of two")]
+    fn test_is_aligned_zero() {
+        let _ = is_aligned(0x12345678, 0);
     }
 }
@@ -1,6 +1,9 @@
-use core::{fmt, ops::Range};
+use core::{
+    fmt,
+    ops::{Bound, Range, RangeBounds, RangeInclusive},
+};
 
-use crate::{MemoryAddr, PhysAddr, VirtAddr};
+use crate::{MemoryAddr, PhysAddr, StrideIter, VirtAddr};
 
 /// A range of a given memory address type `A`.
 ///
@@ -225,6 +228,78 @@ where
         self.end.wrapping_sub_addr(self.start)
     }
 
+    /// Returns the number of pages of `page_size` the range spans, i.e., the
+    /// number of pages touched by the range.
+    ///
+    /// If the endpoints are not aligned to `page_size`, the partial pages at
+    /// both ends are counted as well, as if the range were aligned outward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRange;
+    ///
+    /// assert_eq!(AddrRange::new(0x1000usize, 0x3000).len_pages(0x1000usize), 2);
+    /// assert_eq!(AddrRange::new(0x1800usize, 0x3000).len_pages(0x1000usize), 2);
+    /// assert_eq!(AddrRange::new(0x1800usize, 0x3001).len_pages(0x1000usize), 3);
+    /// assert_eq!(AddrRange::new(0x1000usize, 0x1000).len_pages(0x1000usize), 0);
+    /// ```
+    #[inline]
+    pub fn len_pages<U>(self, page_size: U) -> usize
+    where
+        U: Into<usize>,
+    {
+        let page_size = page_size.into();
+        assert!(
+            page_size != 0,
+            "page size is zero in `AddrRange::len_pages`"
+        );
+        if self.is_empty() {
+            0
+        } else {
+            let first = self.start.into() / page_size;
+            let last = (self.end.into() - 1) / page_size;
+            last - first + 1
+        }
+    }
+
+    /// Returns an iterator over the start addresses of the pages of
+    /// `page_size` the range spans.
+    ///
+    /// If the endpoints are not aligned to `page_size`, the partial pages at
+    /// both ends are included as well, i.e., the iteration starts at `start`
+    /// aligned down, consistent with [`len_pages`](Self::len_pages). An empty
+    /// range yields nothing.
+    ///
+    /// `page_size` must be a power of two, which is checked in debug builds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRange;
+    ///
+    /// let range = AddrRange::new(0x1800usize, 0x3001);
+    /// assert!(range.pages(0x1000usize).eq([0x1000, 0x2000, 0x3000]));
+    /// ```
+    #[inline]
+    pub fn pages<U>(self, page_size: U) -> StrideIter<A>
+    where
+        U: Into<usize>,
+    {
+        let page_size = page_size.into();
+        let start = if self.is_empty() {
+            self.end
+        } else {
+            self.start.align_down(page_size)
+        };
+        StrideIter::new(start, self.end, page_size)
+            .expect("page size is zero in `AddrRange::pages`")
+    }
+
     /// Checks if the range contains the given address.
     ///
     /// # Example
@@ -300,6 +375,93 @@ where
     pub fn overlaps(self, other: Self) -> bool {
         self.start < other.end && other.start < self.end
     }
+
+    /// Shifts both the start and end of the range by the given **signed**
+    /// offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either bound overflows, as [`MemoryAddr::offset`] does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{addr_range, AddrRange};
+    ///
+    /// let range = AddrRange::new(0x1000usize, 0x2000);
+    /// assert_eq!(range.offset(0x800), addr_range!(0x1800usize..0x2800));
+    /// assert_eq!(range.offset(-0x1000), addr_range!(0usize..0x1000));
+    /// ```
+    #[inline]
+    #[must_use = "this returns a new range, without modifying the original"]
+    pub fn offset(self, delta: isize) -> Self {
+        Self {
+            start: self.start.offset(delta),
+            end: self.end.offset(delta),
+        }
+    }
+
+    /// Expands the range to the given alignment, by aligning the start
+    /// downwards and the end upwards.
+    ///
+    /// The result is the smallest aligned range that contains the original
+    /// range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{addr_range, AddrRange};
+    ///
+    /// let range = AddrRange::new(0x1234usize, 0x5678);
+    /// assert_eq!(range.align_outward(0x1000usize), addr_range!(0x1000usize..0x6000));
+    /// ```
+    #[inline]
+    #[must_use = "this returns a new range, without modifying the original"]
+    pub fn align_outward<U>(self, align: U) -> Self
+    where
+        U: Into<usize>,
+    {
+        let align = align.into();
+        Self {
+            start: self.start.align_down(align),
+            end: self.end.align_up(align),
+        }
+    }
+
+    /// Splits the range at `mid` into `[start, mid)` and `[mid, end)`.
+    ///
+    /// Returns `None` if `mid` is not within `[start, end]`. Either part may be
+    /// empty if `mid` is at a boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{addr_range, AddrRange};
+    ///
+    /// let range = AddrRange::new(0x1000usize, 0x3000);
+    /// assert_eq!(
+    ///     range.split_at(0x1800),
+    ///     Some((addr_range!(0x1000usize..0x1800), addr_range!(0x1800usize..0x3000)))
+    /// );
+    /// assert_eq!(range.split_at(0x3001), None);
+    /// ```
+    #[inline]
+    pub fn split_at(self, mid: A) -> Option<(Self, Self)> {
+        if self.start <= mid && mid <= self.end {
+            Some((
+                Self {
+                    start: self.start,
+                    end: mid,
+                },
+                Self {
+                    start: mid,
+                    end: self.end,
+                },
+            ))
+        } else {
+            None
+        }
+    }
 }
 
 /// Conversion from [`Range`] to [`AddrRange`], provided that the type of the
@@ -316,6 +478,55 @@ where
     }
 }
 
+/// Conversion from [`RangeInclusive`] to [`AddrRange`], provided that the type
+/// of the endpoints can be converted to the address type `A`.
+///
+/// Fails if `start > end + 1`, or if the exclusive end overflows.
+impl<A, T> TryFrom<RangeInclusive<T>> for AddrRange<A>
+where
+    A: MemoryAddr + From<T>,
+{
+    type Error = ();
+
+    #[inline]
+    fn try_from(range: RangeInclusive<T>) -> Result<Self, Self::Error> {
+        let (start, end) = range.into_inner();
+        let end = A::from(end).checked_add(1).ok_or(())?;
+        Self::try_new(start.into(), end).ok_or(())
+    }
+}
+
+/// Conversion from [`AddrRange`] to [`Range<usize>`], so that it can be used
+/// to index a byte buffer representing the memory, e.g., `&buf[range.into()]`.
+impl<A> From<AddrRange<A>> for Range<usize>
+where
+    A: MemoryAddr,
+{
+    #[inline]
+    fn from(range: AddrRange<A>) -> Self {
+        range.start.into()..range.end.into()
+    }
+}
+
+/// Implementations of [`RangeBounds`] for [`AddrRange`], so that it can be
+/// passed to APIs accepting generic ranges, e.g. [`BTreeMap::range`].
+///
+/// [`BTreeMap::range`]: https://doc.rust-lang.org/alloc/collections/btree_map/struct.BTreeMap.html#method.range
+impl<A> RangeBounds<A> for AddrRange<A>
+where
+    A: MemoryAddr,
+{
+    #[inline]
+    fn start_bound(&self) -> Bound<&A> {
+        Bound::Included(&self.start)
+    }
+
+    #[inline]
+    fn end_bound(&self) -> Bound<&A> {
+        Bound::Excluded(&self.end)
+    }
+}
+
 /// Implementations of [`Default`] for [`AddrRange`].
 ///
 /// The default value is an empty range `Range { start: 0, end: 0 }`.
@@ -362,11 +573,338 @@ where
     }
 }
 
+/// Checks if the region `[start, start + size)` contains the given address,
+/// without constructing an [`AddrRange`].
+///
+/// The check never overflows, even if `start + size` exceeds `usize::MAX`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::region_contains;
+///
+/// assert!(!region_contains(0x1000usize, 0x1000, 0x0fff));
+/// assert!(region_contains(0x1000usize, 0x1000, 0x1000));
+/// assert!(region_contains(0x1000usize, 0x1000, 0x1fff));
+/// assert!(!region_contains(0x1000usize, 0x1000, 0x2000));
+/// assert!(region_contains(0x1000usize, usize::MAX, usize::MAX));
+/// ```
+#[inline]
+pub fn region_contains<A: MemoryAddr>(start: A, size: usize, addr: A) -> bool {
+    start <= addr && addr.wrapping_sub_addr(start) < size
+}
+
+/// An inclusive range of a given memory address type `A`.
+///
+/// Unlike [`AddrRange`], both the start and the end are inclusive, i.e., `end`
+/// is the last valid address in the range. Therefore such a range is never
+/// empty, and it's **invalid** iff `start > end`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::AddrRangeInclusive;
+///
+/// let range = AddrRangeInclusive::<usize>::new(0x1000, 0x1fff);
+/// assert_eq!(range.start, 0x1000);
+/// assert_eq!(range.end, 0x1fff);
+/// assert!(range.contains(0x1fff));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AddrRangeInclusive<A: MemoryAddr> {
+    /// The lower bound of the range (inclusive).
+    pub start: A,
+    /// The upper bound of the range (inclusive).
+    pub end: A,
+}
+
+/// Methods for [`AddrRangeInclusive`].
+impl<A> AddrRangeInclusive<A>
+where
+    A: MemoryAddr,
+{
+    /// Creates a new inclusive address range from the start and end addresses.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start > end`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRangeInclusive;
+    ///
+    /// let range = AddrRangeInclusive::new(0x1000usize, 0x1fff);
+    /// assert_eq!(range.start, 0x1000);
+    /// assert_eq!(range.end, 0x1fff);
+    /// ```
+    ///
+    /// And this will panic:
+    ///
+    /// ```should_panic
+    /// # use memory_addr::AddrRangeInclusive;
+    /// let _ = AddrRangeInclusive::new(0x2000usize, 0x1fff);
+    /// ```
+    #[inline]
+    pub fn new(start: A, end: A) -> Self {
+        assert!(
+            start <= end,
+            "invalid `AddrRangeInclusive`: {}..={}",
+            start.into(),
+            end.into()
+        );
+        Self { start, end }
+    }
+
+    /// Creates a new inclusive address range from the start and end addresses.
+    ///
+    /// Returns `None` if `start > end`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRangeInclusive;
+    ///
+    /// let range = AddrRangeInclusive::try_new(0x1000usize, 0x1fff).unwrap();
+    /// assert_eq!(range.start, 0x1000);
+    /// assert_eq!(range.end, 0x1fff);
+    /// assert!(AddrRangeInclusive::try_new(0x2000usize, 0x1fff).is_none());
+    /// ```
+    #[inline]
+    pub fn try_new(start: A, end: A) -> Option<Self> {
+        if start <= end {
+            Some(Self { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the size of the range.
+    ///
+    /// Returns `None` if the range covers the whole address space, whose size
+    /// (`usize::MAX + 1`) is not representable by `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRangeInclusive;
+    ///
+    /// assert_eq!(AddrRangeInclusive::new(0x1000usize, 0x1000).size(), Some(1));
+    /// assert_eq!(AddrRangeInclusive::new(0x1000usize, 0x1fff).size(), Some(0x1000));
+    /// assert_eq!(AddrRangeInclusive::new(0usize, usize::MAX).size(), None);
+    /// ```
+    #[inline]
+    pub fn size(self) -> Option<usize> {
+        self.end.wrapping_sub_addr(self.start).checked_add(1)
+    }
+
+    /// Checks if the range contains the given address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::AddrRangeInclusive;
+    ///
+    /// let range = AddrRangeInclusive::new(0x1000usize, 0x1fff);
+    /// assert!(!range.contains(0x0fff));
+    /// assert!(range.contains(0x1000));
+    /// assert!(range.contains(0x1fff));
+    /// assert!(!range.contains(0x2000));
+    /// ```
+    #[inline]
+    pub fn contains(self, addr: A) -> bool {
+        self.start <= addr && addr <= self.end
+    }
+}
+
+/// Conversion from [`AddrRange`] to [`AddrRangeInclusive`].
+///
+/// Fails if the range is empty, as an inclusive range always contains at least
+/// one address.
+impl<A> TryFrom<AddrRange<A>> for AddrRangeInclusive<A>
+where
+    A: MemoryAddr,
+{
+    type Error = ();
+
+    #[inline]
+    fn try_from(range: AddrRange<A>) -> Result<Self, Self::Error> {
+        if range.is_empty() {
+            Err(())
+        } else {
+            Ok(Self {
+                start: range.start,
+                end: range.end.wrapping_sub(1),
+            })
+        }
+    }
+}
+
+/// Conversion from [`AddrRangeInclusive`] to [`AddrRange`].
+///
+/// Fails if the end of the range is `usize::MAX`, as the exclusive end would
+/// overflow.
+impl<A> TryFrom<AddrRangeInclusive<A>> for AddrRange<A>
+where
+    A: MemoryAddr,
+{
+    type Error = ();
+
+    #[inline]
+    fn try_from(range: AddrRangeInclusive<A>) -> Result<Self, Self::Error> {
+        range
+            .end
+            .checked_add(1)
+            .map(|end| Self {
+                start: range.start,
+                end,
+            })
+            .ok_or(())
+    }
+}
+
+/// Implementations of [`Debug`](fmt::Debug) for [`AddrRangeInclusive`].
+impl<A> fmt::Debug for AddrRangeInclusive<A>
+where
+    A: MemoryAddr + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}..={:?}", self.start, self.end)
+    }
+}
+
+/// Implementations of [`LowerHex`](fmt::LowerHex) for [`AddrRangeInclusive`].
+impl<A> fmt::LowerHex for AddrRangeInclusive<A>
+where
+    A: MemoryAddr + fmt::LowerHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:x}..={:x}", self.start, self.end)
+    }
+}
+
+/// Implementations of [`UpperHex`](fmt::UpperHex) for [`AddrRangeInclusive`].
+impl<A> fmt::UpperHex for AddrRangeInclusive<A>
+where
+    A: MemoryAddr + fmt::UpperHex,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:X}..={:X}", self.start, self.end)
+    }
+}
+
 /// A range of virtual addresses [`VirtAddr`].
 pub type VirtAddrRange = AddrRange<VirtAddr>;
 /// A range of physical addresses [`PhysAddr`].
 pub type PhysAddrRange = AddrRange<PhysAddr>;
 
+/// Panics with `"{msg}: {lhs}{op}{rhs}"`, i.e., the same message as the
+/// generic constructors, in `const` contexts where formatting integers with
+/// `panic!` is not supported.
+const fn const_panic_with_operands(msg: &str, lhs: usize, op: &str, rhs: usize) -> ! {
+    const fn push(buf: &mut [u8], mut len: usize, bytes: &[u8]) -> usize {
+        let mut i = 0;
+        while i < bytes.len() {
+            buf[len] = bytes[i];
+            len += 1;
+            i += 1;
+        }
+        len
+    }
+
+    const fn push_usize(buf: &mut [u8], len: usize, mut value: usize) -> usize {
+        let mut digits = [0u8; 20];
+        let mut i = digits.len();
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        push(buf, len, digits.split_at(i).1)
+    }
+
+    let mut buf = [0u8; 128];
+    let mut len = push(&mut buf, 0, msg.as_bytes());
+    len = push(&mut buf, len, b": ");
+    len = push_usize(&mut buf, len, lhs);
+    len = push(&mut buf, len, op.as_bytes());
+    len = push_usize(&mut buf, len, rhs);
+    match core::str::from_utf8(buf.split_at(len).0) {
+        Ok(msg) => panic!("{}", msg),
+        Err(_) => panic!("{}", "invalid panic message"),
+    }
+}
+
+macro_rules! impl_const_constructors {
+    ($($addr:ident),*) => {$(
+        impl AddrRange<$addr> {
+            #[doc = concat!("Creates a new range of [`", stringify!($addr), "`] in `const` context.")]
+            ///
+            /// The generic [`AddrRange::new`] can't be `const`, since it
+            /// compares the addresses via trait methods. This constructor works
+            /// on the concrete address type instead, so it can be used to build
+            /// static range tables.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `start > end`. When evaluated in `const` context (e.g.,
+            /// in a `static` initializer), this becomes a compile-time error.
+            ///
+            /// # Example
+            ///
+            /// ```
+            #[doc = concat!("use memory_addr::{AddrRange, ", stringify!($addr), "};")]
+            ///
+            #[doc = concat!("static TABLE: [AddrRange<", stringify!($addr), ">; 2] = [")]
+            #[doc = concat!("    AddrRange::<", stringify!($addr), ">::new_const(", stringify!($addr), "::from_usize(0x1000), ", stringify!($addr), "::from_usize(0x2000)),")]
+            #[doc = concat!("    AddrRange::<", stringify!($addr), ">::new_const(", stringify!($addr), "::from_usize(0x8000), ", stringify!($addr), "::from_usize(0x9000)),")]
+            /// ];
+            /// assert_eq!(TABLE[1].size(), 0x1000);
+            /// ```
+            #[inline]
+            pub const fn new_const(start: $addr, end: $addr) -> Self {
+                if start.gt_const(end) {
+                    const_panic_with_operands(
+                        "invalid `AddrRange`",
+                        start.as_usize(),
+                        "..",
+                        end.as_usize(),
+                    );
+                }
+                Self { start, end }
+            }
+
+            #[doc = concat!("Creates a new range of [`", stringify!($addr), "`] from the start address and the size in `const` context.")]
+            ///
+            /// See [`Self::new_const`] for the constraints.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `start + size` overflows.
+            #[inline]
+            pub const fn from_start_size_const(start: $addr, size: usize) -> Self {
+                match start.as_usize().checked_add(size) {
+                    Some(end) => Self {
+                        start,
+                        end: $addr::from_usize(end),
+                    },
+                    None => const_panic_with_operands(
+                        "size too large for `AddrRange`",
+                        start.as_usize(),
+                        " + ",
+                        size,
+                    ),
+                }
+            }
+        }
+    )*};
+}
+
+impl_const_constructors!(PhysAddr, VirtAddr);
+
 /// Converts the given range expression into [`AddrRange`]. Panics if the range
 /// is invalid.
 ///
@@ -449,7 +987,12 @@ macro_rules! pa_range {
 
 #[cfg(test)]
 mod test {
-    use crate::{va, va_range, VirtAddrRange};
+    use core::ops::{Bound, Range, RangeBounds};
+
+    use crate::{
+        region_contains, va, va_range, AddrRange, AddrRangeInclusive, PhysAddr, PhysAddrRange,
+        VirtAddrRange,
+    };
 
     #[test]
     fn test_range_format() {
@@ -509,4 +1052,211 @@ mod test {
         assert_eq!(default_range.start, va!(0));
         assert_eq!(default_range.end, va!(0));
     }
+
+    #[test]
+    fn test_range_into_usize_range() {
+        let memory: Vec<u8> = (0..0x40).collect();
+        let range = va_range!(0x10..0x14);
+
+        let bytes = &memory[Range::from(range)];
+        assert_eq!(bytes, [0x10, 0x11, 0x12, 0x13]);
+        let usize_range: Range<usize> = range.into();
+        assert_eq!(usize_range, 0x10..0x14);
+        assert!(memory[Range::from(va_range!(0x20..0x20))].is_empty());
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        use std::collections::BTreeMap;
+
+        let range = VirtAddrRange::try_from(va!(0x1000)..va!(0x2000)).unwrap();
+        assert_eq!(range, va_range!(0x1000..0x2000));
+        assert_eq!(range.start_bound(), Bound::Included(&va!(0x1000)));
+        assert_eq!(range.end_bound(), Bound::Excluded(&va!(0x2000)));
+
+        let map: BTreeMap<_, _> = (0..4).map(|i| (va!(0x800 * i), i)).collect();
+        let found: Vec<_> = map.range(range).map(|(_, v)| *v).collect();
+        assert_eq!(found, [2, 3]);
+
+        let range = VirtAddrRange::try_from(va!(0x1000)..=va!(0x1fff)).unwrap();
+        assert_eq!(range, va_range!(0x1000..0x2000));
+        assert_eq!(va_range!(0x1000..=0x1000).size(), 1);
+        assert!(VirtAddrRange::try_from(va!(0x1000)..=va!(usize::MAX)).is_err());
+        assert!(VirtAddrRange::try_from(va!(0x2000)..=va!(0x1000)).is_err());
+    }
+
+    #[test]
+    fn test_range_offset_and_align_outward() {
+        let range = va_range!(0x1234..0x5678);
+
+        assert_eq!(range.offset(0x1000), va_range!(0x2234..0x6678));
+        assert_eq!(range.offset(-0x1234), va_range!(0x0..0x4444));
+        assert_eq!(range.offset(0).size(), range.size());
+
+        assert_eq!(range.align_outward(0x1000usize), va_range!(0x1000..0x6000));
+        assert_eq!(range.align_outward(1usize), range);
+        let aligned = va_range!(0x1000..0x2000);
+        assert_eq!(aligned.align_outward(0x1000usize), aligned);
+        assert_eq!(
+            aligned.align_outward(0x20_0000usize),
+            va_range!(0x0..0x20_0000)
+        );
+    }
+
+    #[test]
+    fn test_range_len_pages() {
+        // exactly N pages
+        assert_eq!(va_range!(0x1000..0x4000).len_pages(0x1000usize), 3);
+        assert_eq!(va_range!(0x20_0000..0x60_0000).len_pages(0x20_0000usize), 2);
+        // N.5 pages
+        assert_eq!(va_range!(0x1000..0x4800).len_pages(0x1000usize), 4);
+        assert_eq!(va_range!(0x1800..0x5000).len_pages(0x1000usize), 4);
+        assert_eq!(va_range!(0x1800..0x4800).len_pages(0x1000usize), 4);
+        // sub-page ranges
+        assert_eq!(va_range!(0x1800..0x1900).len_pages(0x1000usize), 1);
+        assert_eq!(va_range!(0x1fff..0x2001).len_pages(0x1000usize), 2);
+        assert_eq!(va_range!(0x1800..0x1800).len_pages(0x1000usize), 0);
+        // the whole address space
+        assert_eq!(
+            va_range!(0..usize::MAX).len_pages(0x1000usize),
+            (usize::MAX >> 12) + 1
+        );
+    }
+
+    #[test]
+    fn test_range_pages() {
+        assert!(va_range!(0x1000..0x4000).pages(0x1000usize).eq([
+            va!(0x1000),
+            va!(0x2000),
+            va!(0x3000)
+        ]));
+        assert!(va_range!(0x1800..0x3001).pages(0x1000usize).eq([
+            va!(0x1000),
+            va!(0x2000),
+            va!(0x3000)
+        ]));
+        assert!(va_range!(0x1800..0x1900)
+            .pages(0x1000usize)
+            .eq([va!(0x1000)]));
+        assert!(va_range!(0x1_2345..0x60_0000).pages(0x20_0000usize).eq([
+            va!(0),
+            va!(0x20_0000),
+            va!(0x40_0000)
+        ]));
+        assert_eq!(va_range!(0x1800..0x1800).pages(0x1000usize).count(), 0);
+
+        for range in [
+            va_range!(0x1000..0x4800),
+            va_range!(0x1fff..0x2001),
+            va_range!(0x1234..0x1234),
+        ] {
+            assert_eq!(
+                range.pages(0x1000usize).count(),
+                range.len_pages(0x1000usize)
+            );
+        }
+
+        // terminates at the top of the address space
+        let range = va_range!(usize::MAX - 0x1fff..usize::MAX);
+        assert!(range
+            .pages(0x1000usize)
+            .eq([va!(usize::MAX - 0x1fff), va!(usize::MAX - 0xfff)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "page size is zero in `AddrRange::len_pages`")]
+    fn test_range_len_pages_zero() {
+        let _ = va_range!(0x1000..0x2000).len_pages(0usize);
+    }
+
+    #[test]
+    fn test_range_split_at() {
+        let range = va_range!(0x1000..0x3000);
+
+        let (low, high) = range.split_at(va!(0x2000)).unwrap();
+        assert_eq!(low, va_range!(0x1000..0x2000));
+        assert_eq!(high, va_range!(0x2000..0x3000));
+        assert_eq!(low.size() + high.size(), range.size());
+
+        let (low, high) = range.split_at(va!(0x1000)).unwrap();
+        assert!(low.is_empty());
+        assert_eq!(high, range);
+        let (low, high) = range.split_at(va!(0x3000)).unwrap();
+        assert_eq!(low, range);
+        assert!(high.is_empty());
+
+        assert_eq!(range.split_at(va!(0xfff)), None);
+        assert_eq!(range.split_at(va!(0x3001)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in `MemoryAddr::offset`")]
+    fn test_range_offset_overflow() {
+        let _ = va_range!(0x1000..0x2000).offset(-0x1001);
+    }
+
+    #[test]
+    fn test_range_const() {
+        static RESERVED: [PhysAddrRange; 2] = [
+            PhysAddrRange::new_const(PhysAddr::from_usize(0x0), PhysAddr::from_usize(0x1000)),
+            PhysAddrRange::from_start_size_const(PhysAddr::from_usize(0x8000_0000), 0x20_0000),
+        ];
+        assert_eq!(RESERVED[0], pa_range!(0x0..0x1000));
+        assert_eq!(RESERVED[1], pa_range!(0x8000_0000..0x8020_0000));
+
+        const EMPTY: VirtAddrRange = VirtAddrRange::new_const(va!(0x1000), va!(0x1000));
+        assert!(EMPTY.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid `AddrRange`: 8192..4096")]
+    fn test_range_const_invalid() {
+        let _ =
+            PhysAddrRange::new_const(PhysAddr::from_usize(0x2000), PhysAddr::from_usize(0x1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "size too large for `AddrRange`: 4096 + ")]
+    fn test_range_const_size_overflow() {
+        let _ = VirtAddrRange::from_start_size_const(va!(0x1000), usize::MAX);
+    }
+
+    #[test]
+    fn test_region_contains() {
+        let start = va!(0x1000);
+        assert!(!region_contains(start, 0x1000, va!(0xfff)));
+        assert!(region_contains(start, 0x1000, va!(0x1000)));
+        assert!(region_contains(start, 0x1000, va!(0x1fff)));
+        assert!(!region_contains(start, 0x1000, va!(0x2000)));
+        assert!(!region_contains(start, 0, start));
+
+        // `start + size` overflows.
+        let start = va!(usize::MAX - 0xfff);
+        assert!(region_contains(start, 0x2000, va!(usize::MAX)));
+        assert!(!region_contains(start, 0x2000, va!(0)));
+    }
+
+    #[test]
+    fn test_range_inclusive() {
+        let range = AddrRangeInclusive::new(va!(0x1000), va!(0x1fff));
+        assert!(!range.contains(va!(0xfff)));
+        assert!(range.contains(va!(0x1000)));
+        assert!(range.contains(va!(0x1fff)));
+        assert!(!range.contains(va!(0x2000)));
+        assert_eq!(range.size(), Some(0x1000));
+        assert_eq!(format!("{:?}", range), "VA:0x1000..=VA:0x1fff");
+
+        let exclusive = AddrRange::try_from(range).unwrap();
+        assert_eq!(exclusive, va_range!(0x1000..0x2000));
+        assert_eq!(AddrRangeInclusive::try_from(exclusive), Ok(range));
+        assert!(AddrRangeInclusive::try_from(va_range!(0x1000..0x1000)).is_err());
+
+        // The whole address space can't be converted to an exclusive range.
+        let full = AddrRangeInclusive::new(va!(0), va!(usize::MAX));
+        assert!(full.contains(va!(usize::MAX)));
+        assert_eq!(full.size(), None);
+        assert!(AddrRange::try_from(full).is_err());
+        let top = AddrRange::try_from(AddrRangeInclusive::new(va!(0x1000), va!(usize::MAX - 1)));
+        assert_eq!(top, Ok(va_range!(0x1000..usize::MAX)));
+    }
 }
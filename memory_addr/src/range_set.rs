@@ -0,0 +1,441 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{AddrRange, MemoryAddr};
+
+/// A set of addresses of a given memory address type `A`, stored as sorted,
+/// non-overlapping, and non-adjacent [`AddrRange`]s.
+///
+/// Overlapping or adjacent ranges are merged on insertion, and ranges are
+/// split on removal if necessary, so that each address range in the set is
+/// always maximal.
+///
+/// This type is only available with the `alloc` feature.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{addr_range, AddrRangeSet};
+///
+/// let mut set = AddrRangeSet::new();
+/// set.insert(addr_range!(0x1000usize..0x2000));
+/// set.insert(addr_range!(0x2000usize..0x3000));
+/// set.insert(addr_range!(0x5000usize..0x6000));
+/// assert_eq!(set.len(), 2);
+///
+/// set.remove(addr_range!(0x1800usize..0x2800));
+/// assert!(set.contains(0x1000));
+/// assert!(!set.contains(0x2000));
+/// assert!(set.iter().eq([
+///     addr_range!(0x1000usize..0x1800),
+///     addr_range!(0x2800usize..0x3000),
+///     addr_range!(0x5000usize..0x6000),
+/// ]));
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct AddrRangeSet<A: MemoryAddr> {
+    ranges: Vec<AddrRange<A>>,
+}
+
+/// Methods for [`AddrRangeSet`].
+impl<A> AddrRangeSet<A>
+where
+    A: MemoryAddr,
+{
+    /// Creates a new empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Returns the number of (maximal) address ranges in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Checks if the set contains no addresses.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Checks if the set contains the given address.
+    pub fn contains(&self, addr: A) -> bool {
+        let idx = self.ranges.partition_point(|r| r.end <= addr);
+        self.ranges.get(idx).is_some_and(|r| r.contains(addr))
+    }
+
+    /// Adds an address range to the set, merging it with the overlapping and
+    /// adjacent ranges.
+    ///
+    /// Empty ranges are ignored.
+    pub fn insert(&mut self, range: AddrRange<A>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges in `first..last` overlap with or are adjacent to `range`.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        if first == last {
+            self.ranges.insert(first, range);
+        } else {
+            let merged = AddrRange {
+                start: range.start.min(self.ranges[first].start),
+                end: range.end.max(self.ranges[last - 1].end),
+            };
+            self.ranges.splice(first..last, [merged]);
+        }
+    }
+
+    /// Removes an address range from the set, splitting the ranges that
+    /// partially overlap with it.
+    ///
+    /// Empty ranges are ignored.
+    pub fn remove(&mut self, range: AddrRange<A>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges in `first..last` overlap with `range`.
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+        let head = self.ranges[first].start;
+        let tail = self.ranges[last - 1].end;
+        let remains = [
+            AddrRange {
+                start: head,
+                end: range.start,
+            },
+            AddrRange {
+                start: range.end,
+                end: tail,
+            },
+        ];
+        self.ranges
+            .splice(first..last, remains.into_iter().filter(|r| r.start < r.end));
+    }
+
+    /// Removes all addresses from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// Returns an iterator over the (maximal) address ranges in the set, in
+    /// ascending order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = AddrRange<A>> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// Returns the set of addresses that are in `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ranges: Vec<AddrRange<A>> = Vec::with_capacity(self.len() + other.len());
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x.start <= y.start => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+            let Some(&range) = next else {
+                break;
+            };
+            match ranges.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Returns the set of addresses that are in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.len() && j < other.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(AddrRange { start, end });
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Returns the set of addresses that are in `self` but not in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use memory_addr::{pa_range, AddrRangeSet};
+    ///
+    /// let ram: AddrRangeSet<_> = [pa_range!(0x8000_0000..0x8800_0000)].into_iter().collect();
+    /// let reserved: AddrRangeSet<_> = [
+    ///     pa_range!(0x8000_0000..0x8020_0000), // kernel image
+    ///     pa_range!(0x8400_0000..0x8410_0000), // device tree
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let free = ram.difference(&reserved);
+    /// assert!(free.iter().eq([
+    ///     pa_range!(0x8020_0000..0x8400_0000),
+    ///     pa_range!(0x8410_0000..0x8800_0000),
+    /// ]));
+    /// ```
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for &range in &self.ranges {
+            let mut start = range.start;
+            // Skip the ranges in `other` that end before the remaining part.
+            while j < other.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            for hole in other.ranges[j..].iter().take_while(|r| r.start < range.end) {
+                if start < hole.start {
+                    ranges.push(AddrRange {
+                        start,
+                        end: hole.start,
+                    });
+                }
+                start = start.max(hole.end);
+            }
+            if start < range.end {
+                ranges.push(AddrRange {
+                    start,
+                    end: range.end,
+                });
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl<A> Default for AddrRangeSet<A>
+where
+    A: MemoryAddr,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects address ranges into a set, as if they're inserted one by one.
+impl<A> FromIterator<AddrRange<A>> for AddrRangeSet<A>
+where
+    A: MemoryAddr,
+{
+    fn from_iter<I: IntoIterator<Item = AddrRange<A>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<A> Extend<AddrRange<A>> for AddrRangeSet<A>
+where
+    A: MemoryAddr,
+{
+    fn extend<I: IntoIterator<Item = AddrRange<A>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+/// Implementations of [`Debug`](fmt::Debug) for [`AddrRangeSet`].
+impl<A> fmt::Debug for AddrRangeSet<A>
+where
+    A: MemoryAddr + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.ranges.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{addr_range, va, va_range, AddrRange, AddrRangeSet, VirtAddr};
+
+    fn ranges(set: &AddrRangeSet<usize>) -> Vec<AddrRange<usize>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_range_set_insert() {
+        let mut set = AddrRangeSet::new();
+        assert!(set.is_empty());
+
+        set.insert(addr_range!(0x3000usize..0x4000));
+        set.insert(addr_range!(0x1000usize..0x2000));
+        set.insert(addr_range!(0x6000usize..0x7000));
+        set.insert(addr_range!(0x5000usize..0x5000)); // empty, ignored
+        assert_eq!(
+            ranges(&set),
+            [
+                addr_range!(0x1000usize..0x2000),
+                addr_range!(0x3000usize..0x4000),
+                addr_range!(0x6000usize..0x7000),
+            ]
+        );
+
+        // adjacent on both sides
+        set.insert(addr_range!(0x2000usize..0x3000));
+        assert_eq!(
+            ranges(&set),
+            [
+                addr_range!(0x1000usize..0x4000),
+                addr_range!(0x6000usize..0x7000)
+            ]
+        );
+
+        // overlapping with multiple ranges
+        set.insert(addr_range!(0x800usize..0x6800));
+        assert_eq!(ranges(&set), [addr_range!(0x800usize..0x7000)]);
+
+        // contained in an existing range
+        set.insert(addr_range!(0x1000usize..0x2000));
+        assert_eq!(ranges(&set), [addr_range!(0x800usize..0x7000)]);
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_range_set_remove() {
+        let mut set: AddrRangeSet<usize> = [
+            addr_range!(0x1000usize..0x4000),
+            addr_range!(0x5000usize..0x6000),
+            addr_range!(0x8000usize..0x9000),
+        ]
+        .into_iter()
+        .collect();
+
+        // split a range
+        set.remove(addr_range!(0x2000usize..0x3000));
+        assert_eq!(
+            ranges(&set),
+            [
+                addr_range!(0x1000usize..0x2000),
+                addr_range!(0x3000usize..0x4000),
+                addr_range!(0x5000usize..0x6000),
+                addr_range!(0x8000usize..0x9000),
+            ]
+        );
+
+        // shrink two ranges and remove the one in between
+        set.remove(addr_range!(0x3800usize..0x8800));
+        assert_eq!(
+            ranges(&set),
+            [
+                addr_range!(0x1000usize..0x2000),
+                addr_range!(0x3000usize..0x3800),
+                addr_range!(0x8800usize..0x9000),
+            ]
+        );
+
+        // not overlapping, or empty
+        set.remove(addr_range!(0x2000usize..0x3000));
+        set.remove(addr_range!(0x1800usize..0x1800));
+        assert_eq!(set.len(), 3);
+
+        set.remove(addr_range!(0usize..usize::MAX));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_range_set_contains() {
+        let mut set = AddrRangeSet::<VirtAddr>::default();
+        set.insert(va_range!(0x1000..0x2000));
+        set.insert(va_range!(0x3000..0x4000));
+
+        assert!(!set.contains(va!(0xfff)));
+        assert!(set.contains(va!(0x1000)));
+        assert!(set.contains(va!(0x1fff)));
+        assert!(!set.contains(va!(0x2000)));
+        assert!(set.contains(va!(0x3800)));
+        assert!(!set.contains(va!(0x4000)));
+
+        assert_eq!(
+            format!("{:?}", set),
+            "{VA:0x1000..VA:0x2000, VA:0x3000..VA:0x4000}"
+        );
+
+        set.clear();
+        assert!(!set.contains(va!(0x1000)));
+    }
+
+    #[test]
+    fn test_range_set_operations() {
+        let a: AddrRangeSet<usize> = [
+            addr_range!(0x1000usize..0x3000),
+            addr_range!(0x5000usize..0x6000),
+            addr_range!(0x8000usize..0x9000),
+        ]
+        .into_iter()
+        .collect();
+        let b: AddrRangeSet<usize> = [
+            addr_range!(0x3000usize..0x4000), // touching
+            addr_range!(0x5400usize..0x5800), // fully contained
+            addr_range!(0x8800usize..0xa000), // overlapping
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            ranges(&a.union(&b)),
+            [
+                addr_range!(0x1000usize..0x4000),
+                addr_range!(0x5000usize..0x6000),
+                addr_range!(0x8000usize..0xa000),
+            ]
+        );
+        assert_eq!(a.union(&b), b.union(&a));
+
+        assert_eq!(
+            ranges(&a.intersection(&b)),
+            [
+                addr_range!(0x5400usize..0x5800),
+                addr_range!(0x8800usize..0x9000),
+            ]
+        );
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+
+        assert_eq!(
+            ranges(&a.difference(&b)),
+            [
+                addr_range!(0x1000usize..0x3000),
+                addr_range!(0x5000usize..0x5400),
+                addr_range!(0x5800usize..0x6000),
+                addr_range!(0x8000usize..0x8800),
+            ]
+        );
+        assert_eq!(
+            ranges(&b.difference(&a)),
+            [
+                addr_range!(0x3000usize..0x4000),
+                addr_range!(0x9000usize..0xa000)
+            ]
+        );
+
+        let empty = AddrRangeSet::new();
+        assert_eq!(a.union(&empty), a);
+        assert!(a.intersection(&empty).is_empty());
+        assert_eq!(a.difference(&empty), a);
+        assert!(a.difference(&a).is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::MemoryAddr;
+
+/// A marker trait for address spaces.
+///
+/// Address spaces are used to brand address types, so that generic code can
+/// require multiple address types to belong to the same address space. See
+/// [`HasAddrSpace`] for details.
+pub trait AddrSpace {}
+
+/// A trait for address types that belong to a specific address space.
+///
+/// It's implemented by [`def_usize_addr`](crate::def_usize_addr) for types
+/// declared with `$vis type $name in $space;`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::{HasAddrSpace, PhysAddr, VirtAddr};
+///
+/// fn distance<A, B>(a: A, b: B) -> usize
+/// where
+///     A: HasAddrSpace,
+///     B: HasAddrSpace<Space = A::Space>,
+/// {
+///     a.into().abs_diff(b.into())
+/// }
+///
+/// assert_eq!(distance(PhysAddr::from(0x2000), PhysAddr::from(0x1000)), 0x1000);
+/// ```
+///
+/// And this won't compile:
+///
+/// ```compile_fail
+/// # use memory_addr::{HasAddrSpace, PhysAddr, VirtAddr};
+/// # fn distance<A, B>(a: A, b: B) -> usize
+/// # where
+/// #     A: HasAddrSpace,
+/// #     B: HasAddrSpace<Space = A::Space>,
+/// # {
+/// #     a.into().abs_diff(b.into())
+/// # }
+/// let _ = distance(PhysAddr::from(0x2000), VirtAddr::from(0x1000));
+/// ```
+pub trait HasAddrSpace: MemoryAddr {
+    /// The address space that the address type belongs to.
+    type Space: AddrSpace;
+}
+
+/// The physical address space, which [`PhysAddr`](crate::PhysAddr) belongs to.
+pub enum PhysAddrSpace {}
+
+impl AddrSpace for PhysAddrSpace {}
+
+/// The virtual address space, which [`VirtAddr`](crate::VirtAddr) belongs to.
+pub enum VirtAddrSpace {}
+
+impl AddrSpace for VirtAddrSpace {}
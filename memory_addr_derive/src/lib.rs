@@ -0,0 +1,167 @@
+#![doc = include_str!("../README.md")]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Type};
+
+/// Derives the conversions and operators of an address type for a tuple
+/// struct wrapping a single `usize`, e.g., `struct Foo(usize);`.
+///
+/// It generates the same items as `memory_addr::def_usize_addr!` for the
+/// conversions and operators:
+/// - Two `const` methods `from_usize` and `as_usize` to convert between the
+///   address type and `usize`.
+/// - Implementations for the following traits:
+///   - `From<usize>`, `Into<usize>` (by implementing `From<Foo> for usize`),
+///   - `Add<usize>`, `AddAssign<usize>`, `Sub<usize>`, `SubAssign<usize>`, and
+///   - `Sub<Foo>`.
+///
+/// Unlike `def_usize_addr!`, other traits (e.g., `Copy` and `Ord`) are not
+/// derived automatically, so that they can be chosen freely. Deriving `Copy`
+/// and `Ord` as well makes the type implement `memory_addr::MemoryAddr`.
+///
+/// # Example
+///
+/// ```
+/// use memory_addr::MemoryAddr;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, MemoryAddr)]
+/// struct UserAddr(usize);
+///
+/// fn is_user_page(addr: impl MemoryAddr) -> bool {
+///     addr.is_aligned_4k() && addr.into() < 0x8000_0000
+/// }
+///
+/// assert!(is_user_page(UserAddr::from_usize(0x1000)));
+/// let mut addr = UserAddr::from(0x2000);
+/// addr -= 0x800;
+/// assert_eq!(usize::from(addr), 0x1800);
+/// ```
+///
+/// And this won't compile, since the struct doesn't wrap a single field:
+///
+/// ```compile_fail
+/// use memory_addr::MemoryAddr;
+///
+/// #[derive(MemoryAddr)]
+/// struct Region {
+///     start: usize,
+///     size: usize,
+/// }
+/// ```
+///
+/// Nor will this, since the wrapped field isn't a `usize`:
+///
+/// ```compile_fail
+/// use memory_addr::MemoryAddr;
+///
+/// #[derive(MemoryAddr)]
+/// struct Addr32(u32);
+/// ```
+#[proc_macro_derive(MemoryAddr)]
+pub fn derive_memory_addr(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    impl_memory_addr(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn impl_memory_addr(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let field = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(f) if f.unnamed.len() == 1 => f.unnamed.first(),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(field) = field else {
+        return Err(Error::new_spanned(
+            input,
+            "`MemoryAddr` can only be derived for tuple structs with a single `usize` field",
+        ));
+    };
+    let is_usize = match &field.ty {
+        Type::Path(ty) => ty.qself.is_none() && ty.path.is_ident("usize"),
+        _ => false,
+    };
+    if !is_usize {
+        return Err(Error::new_spanned(
+            &field.ty,
+            "the field of a `MemoryAddr` struct must be a `usize`",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let from_usize_doc = format!("Converts an `usize` to an [`{name}`].");
+    let as_usize_doc = format!("Converts an [`{name}`] to an `usize`.");
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #from_usize_doc]
+            #[inline]
+            pub const fn from_usize(addr: usize) -> Self {
+                Self(addr)
+            }
+
+            #[doc = #as_usize_doc]
+            #[inline]
+            pub const fn as_usize(self) -> usize {
+                self.0
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<usize> for #name #ty_generics #where_clause {
+            #[inline]
+            fn from(addr: usize) -> Self {
+                Self(addr)
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for usize #where_clause {
+            #[inline]
+            fn from(addr: #name #ty_generics) -> usize {
+                addr.0
+            }
+        }
+
+        impl #impl_generics ::core::ops::Add<usize> for #name #ty_generics #where_clause {
+            type Output = Self;
+            #[inline]
+            fn add(self, rhs: usize) -> Self {
+                Self(self.0 + rhs)
+            }
+        }
+
+        impl #impl_generics ::core::ops::AddAssign<usize> for #name #ty_generics #where_clause {
+            #[inline]
+            fn add_assign(&mut self, rhs: usize) {
+                self.0 += rhs;
+            }
+        }
+
+        impl #impl_generics ::core::ops::Sub<usize> for #name #ty_generics #where_clause {
+            type Output = Self;
+            #[inline]
+            fn sub(self, rhs: usize) -> Self {
+                Self(self.0 - rhs)
+            }
+        }
+
+        impl #impl_generics ::core::ops::SubAssign<usize> for #name #ty_generics #where_clause {
+            #[inline]
+            fn sub_assign(&mut self, rhs: usize) {
+                self.0 -= rhs;
+            }
+        }
+
+        impl #impl_generics ::core::ops::Sub<#name #ty_generics> for #name #ty_generics #where_clause {
+            type Output = usize;
+            #[inline]
+            fn sub(self, rhs: #name #ty_generics) -> usize {
+                self.0 - rhs.0
+            }
+        }
+    })
+}